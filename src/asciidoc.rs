@@ -0,0 +1,242 @@
+//! AsciiDoc rendering of the article block tree
+
+use crate::blocks::{render_spans, single_line, Block, EmbedKind, Mark, Span, TableRow};
+
+/// Render blocks as the body of an AsciiDoc document
+pub fn blocks_to_asciidoc(blocks: &[Block]) -> String {
+    let mut asciidoc = render_blocks(blocks, 0).join("\n\n");
+    if !asciidoc.is_empty() {
+        asciidoc.push('\n');
+    }
+    asciidoc
+}
+
+/// `quote_depth` is the number of enclosing quotes, which sets the length
+/// of quote delimiters
+fn render_blocks(blocks: &[Block], quote_depth: usize) -> Vec<String> {
+    blocks.iter()
+        .map(|block| render_block(block, 0, quote_depth))
+        .filter(|block| !block.is_empty())
+        .collect()
+}
+
+/// `depth` is the nesting of the list the block is in, which sets the
+/// length of list markers
+fn render_block(block: &Block, depth: usize, quote_depth: usize) -> String {
+    match block {
+        Block::Heading { level, spans } => {
+            // Level 0 is the document title
+            let marker = "=".repeat((*level as usize + 1).min(6));
+            format!("{} {}", marker, render_inline(&single_line(spans)))
+        }
+        Block::Paragraph { spans } => render_inline(spans),
+        Block::List { ordered, start, items } => render_list(*ordered, *start, items, depth, quote_depth),
+        Block::Quote { blocks } => {
+            // A nested quote needs a longer delimiter than the one around it
+            let delimiter = "_".repeat(4 + quote_depth);
+            format!("{}\n{}\n{}", delimiter, render_blocks(blocks, quote_depth + 1).join("\n\n"), delimiter)
+        }
+        Block::Code { lang, text } => {
+            // The delimiter has to be longer than any line of dashes in the code
+            let longest = text.lines()
+                .filter(|line| !line.is_empty() && line.chars().all(|c| c == '-'))
+                .map(str::len)
+                .max()
+                .unwrap_or(0);
+            let delimiter = "-".repeat(longest.max(3) + 1);
+            let header = match lang {
+                Some(lang) => format!("[source,{}]\n", lang),
+                None => String::new(),
+            };
+            format!("{}{}\n{}\n{}", header, delimiter, text, delimiter)
+        }
+        Block::Image { src, alt, caption, link } => {
+            let mut image = String::new();
+            if let Some(caption) = caption {
+                image.push_str(&format!(".{}\n", escape_text(caption)));
+            }
+            let alt = alt.as_deref().unwrap_or("").replace('"', "&quot;").replace(']', "&#93;");
+            match link {
+                Some(link) => image.push_str(&format!("image::{}[\"{}\",link=\"{}\"]", escape_url(src), alt, escape_url(link).replace('"', "%22"))),
+                None => image.push_str(&format!("image::{}[\"{}\"]", escape_url(src), alt)),
+            }
+            image
+        }
+        Block::Table { rows } => render_table(rows),
+        Block::Embed { kind, src } => match kind {
+            EmbedKind::Video => format!("video::{}[]", escape_url(src)),
+            EmbedKind::Audio => format!("audio::{}[]", escape_url(src)),
+            EmbedKind::Iframe | EmbedKind::Object => format!("link:{}[]", escape_url(src)),
+        },
+        Block::Rule => "'''".to_string(),
+    }
+}
+
+fn render_list(ordered: bool, start: Option<u32>, items: &[Vec<Block>], depth: usize, quote_depth: usize) -> String {
+    let marker = if ordered { "." } else { "*" }.repeat(depth + 1);
+    let mut lines = Vec::new();
+    if let (true, Some(start)) = (ordered, start) {
+        if start != 1 {
+            lines.push(format!("[start={}]", start));
+        }
+    }
+
+    for item in items {
+        let mut blocks = item.iter().peekable();
+        let mut rendered = match blocks.next_if(|block| matches!(block, Block::Paragraph { .. })) {
+            Some(paragraph) => format!("{} {}", marker, render_block(paragraph, depth, quote_depth)),
+            None => format!("{} {{empty}}", marker),
+        };
+        for block in blocks {
+            let content = render_block(block, depth + 1, quote_depth);
+            if content.is_empty() {
+                continue;
+            }
+            // Nested lists follow the item directly, other blocks are
+            // attached to it with a list continuation
+            if matches!(block, Block::List { .. }) {
+                rendered.push_str(&format!("\n{}", content));
+            } else {
+                rendered.push_str(&format!("\n+\n{}", content));
+            }
+        }
+        lines.push(rendered);
+    }
+
+    lines.join("\n")
+}
+
+fn render_table(rows: &[TableRow]) -> String {
+    let columns = rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+
+    let mut lines = Vec::new();
+    if rows[0].header {
+        lines.push("[%header]".to_string());
+    }
+    lines.push("|===".to_string());
+    for row in rows {
+        let mut cells: Vec<String> = row.cells.iter()
+            .map(|cell| render_inline(&single_line(cell)).replace('|', "{vbar}"))
+            .collect();
+        cells.resize(columns, String::new());
+        lines.push(cells.iter().map(|cell| format!("|{}", cell)).collect::<Vec<_>>().join(" "));
+    }
+    lines.push("|===".to_string());
+    lines.join("\n")
+}
+
+fn render_inline(spans: &[Span]) -> String {
+    let rendered = render_spans(
+        spans,
+        escape_text,
+        |mark, text| match mark {
+            Mark::Bold => format!("**{}**", text),
+            Mark::Italic => format!("__{}__", text),
+            Mark::Underline => format!("[.underline]##{}##", text),
+            Mark::Strikethrough => format!("[.line-through]##{}##", text),
+            Mark::Code => format!("``{}``", text),
+            Mark::Superscript => format!("^{}^", text),
+            Mark::Subscript => format!("~{}~", text),
+        },
+        |target, text| format!("link:{}[{}]", escape_url(target), text),
+    );
+
+    // Lines AsciiDoc would read as titles, block titles, lists, comments or
+    // attribute entries are started with an empty attribute reference
+    rendered.split('\n')
+        .map(|line| {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            if line.starts_with(['=', '.', '-', '/', ':', '\'', '|']) || (digits > 0 && line[digits..].starts_with('.')) {
+                format!("{{empty}}{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Replace characters AsciiDoc would take as markup or attribute
+/// references by attribute or character references, and turn line breaks into hard breaks
+pub(crate) fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '*' => escaped.push_str("{asterisk}"),
+            '`' => escaped.push_str("{backtick}"),
+            '^' => escaped.push_str("{caret}"),
+            '~' => escaped.push_str("{tilde}"),
+            '+' => escaped.push_str("{plus}"),
+            '[' => escaped.push_str("{startsb}"),
+            ']' => escaped.push_str("{endsb}"),
+            '\\' => escaped.push_str("{backslash}"),
+            '{' => escaped.push_str("&#123;"),
+            '_' => escaped.push_str("&#95;"),
+            '#' => escaped.push_str("&#35;"),
+            '<' => escaped.push_str("&lt;"),
+            '\n' => escaped.push_str(" +\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_url(url: &str) -> String {
+    url.replace(' ', "%20").replace('[', "%5B").replace(']', "%5D")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::html_to_blocks;
+
+    #[test]
+    fn test_asciidoc_inline() {
+        let blocks = html_to_blocks(r#"<h2>Intro to <em>C++</em></h2>
+            <p>Use <strong>snake_case </strong>names and <code>a*b</code>, see <a href="https://example.com/a b">the [docs]</a><br>next</p>"#);
+        assert_eq!(
+            blocks_to_asciidoc(&blocks),
+            "=== Intro to __C{plus}{plus}__\n\n\
+             Use **snake&#95;case** names and ``a{asterisk}b``, see link:https://example.com/a%20b[the {startsb}docs{endsb}] +\nnext\n"
+        );
+    }
+
+    #[test]
+    fn test_asciidoc_blocks() {
+        let blocks = html_to_blocks(r#"<ol start="3"><li>Three<ul><li>Nested</li></ul></li><li><p>Four</p><pre>code</pre></li></ol>
+            <blockquote><p>Quoted</p></blockquote><pre><code class="language-sh">echo ----</code></pre>
+            <figure><img src="a.jpg" alt="An image"><figcaption>Caption</figcaption></figure>
+            <table><tr><th>A</th><th>B</th></tr><tr><td>1|2</td></tr></table><hr>"#);
+        assert_eq!(
+            blocks_to_asciidoc(&blocks),
+            "[start=3]\n. Three\n** Nested\n. Four\n+\n----\ncode\n----\n\n\
+             ____\nQuoted\n____\n\n[source,sh]\n----\necho ----\n----\n\n\
+             .Caption\nimage::a.jpg[\"An image\"]\n\n\
+             [%header]\n|===\n|A |B\n|1{vbar}2 |\n|===\n\n'''\n"
+        );
+    }
+
+    #[test]
+    fn test_asciidoc_linked_image() {
+        let blocks = html_to_blocks(r#"<a href="https://example.com/big.jpg"><img src="small.jpg" alt="Small"></a>"#);
+        assert_eq!(blocks_to_asciidoc(&blocks), "image::small.jpg[\"Small\",link=\"https://example.com/big.jpg\"]\n");
+    }
+
+    #[test]
+    fn test_asciidoc_line_starts() {
+        let blocks = html_to_blocks("<p>= not a title</p><p>. block title<br>- dash<br>1. one</p><p>A = B.</p>");
+        assert_eq!(
+            blocks_to_asciidoc(&blocks),
+            "{empty}= not a title\n\n{empty}. block title +\n{empty}- dash +\n{empty}1. one\n\nA = B.\n"
+        );
+    }
+
+    #[test]
+    fn test_asciidoc_nested_quotes() {
+        let blocks = html_to_blocks("<blockquote><p>Outer</p><blockquote><p>Inner {lang}</p></blockquote></blockquote>");
+        assert_eq!(blocks_to_asciidoc(&blocks), "____\nOuter\n\n_____\nInner &#123;lang}\n_____\n____\n");
+    }
+}
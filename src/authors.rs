@@ -0,0 +1,176 @@
+//! Structured author extraction for the Readability parser
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::json_ld;
+use crate::regexps::get_regexps;
+use crate::utils::{normalize_whitespace, word_count};
+
+/// Names with more words than this are sentences, not author names
+const MAX_AUTHOR_NAME_WORDS: usize = 6;
+
+/// A single author of an article
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Author {
+    /// Display name
+    pub name: String,
+    /// Profile or homepage URL
+    pub url: Option<String>,
+    /// Role or job title, when the page states one
+    pub role: Option<String>,
+}
+
+impl Author {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Split a byline such as "By Jane Doe (@jane) and John Roe, Staff Writer,
+/// May 5, 2021" into author names, dropping handles, dates and staff titles
+pub fn split_byline(byline: &str) -> Vec<String> {
+    let regexps = get_regexps();
+
+    let byline = regexps.byline_date.replace_all(byline, " ");
+    let byline = regexps.twitter_handle.replace_all(&byline, " ");
+
+    regexps.byline_separator.split(&byline)
+        .map(|part| {
+            let is_separator = |c: char| c.is_whitespace() || "|-–—:()".contains(c);
+            let part = normalize_whitespace(part);
+            let part = regexps.staff_suffix.replace(part.trim_matches(is_separator), "");
+            let part = part.trim_matches(is_separator);
+            ["By ", "by ", "BY "].iter()
+                .find_map(|prefix| part.strip_prefix(prefix))
+                .unwrap_or(part)
+                .trim()
+                .to_string()
+        })
+        .filter(|name| is_plausible_name(name))
+        .collect()
+}
+
+/// Authors listed in the `author` property of a JSON-LD article object
+pub fn from_json_ld(article: &Value) -> Vec<Author> {
+    let to_author = |value: &Value| -> Option<Author> {
+        match value {
+            Value::String(name) => Some(Author::new(name.trim())),
+            Value::Object(_) => {
+                // schema.org Role objects wrap the actual author
+                let (person, role) = match value.get("author") {
+                    Some(person) => (person, json_ld::get_string(value, "roleName")),
+                    None => (value, None),
+                };
+                let name = match person {
+                    Value::String(name) => name.trim().to_string(),
+                    _ => json_ld::get_string(person, "name")?,
+                };
+                let url = json_ld::get_string(person, "url").or_else(|| match person.get("sameAs") {
+                    Some(Value::String(same_as)) => Some(same_as.trim().to_string()),
+                    Some(Value::Array(same_as)) => same_as.first().and_then(Value::as_str).map(str::to_string),
+                    _ => None,
+                });
+                Some(Author {
+                    name,
+                    url,
+                    role: role.or_else(|| json_ld::get_string(person, "jobTitle")),
+                })
+            }
+            _ => None,
+        }
+    };
+
+    match article.get("author") {
+        Some(Value::Array(authors)) => authors.iter().filter_map(to_author).collect(),
+        Some(author) => to_author(author).into_iter().collect(),
+        None => Vec::new(),
+    }
+    .into_iter()
+    .filter(|author| !author.name.is_empty())
+    .collect()
+}
+
+/// Merge author lists from several sources, most trusted first. Authors are
+/// matched by name so later sources only fill in missing URLs and roles.
+pub fn merge_authors(sources: Vec<Vec<Author>>) -> Vec<Author> {
+    let mut merged: Vec<Author> = Vec::new();
+
+    for author in sources.into_iter().flatten() {
+        let key = author.name.to_lowercase();
+        match merged.iter_mut().find(|existing| existing.name.to_lowercase() == key) {
+            Some(existing) => {
+                if existing.url.is_none() {
+                    existing.url = author.url;
+                }
+                if existing.role.is_none() {
+                    existing.role = author.role;
+                }
+            }
+            None => merged.push(author),
+        }
+    }
+
+    merged
+}
+
+fn is_plausible_name(name: &str) -> bool {
+    let words = word_count(name);
+    words > 0
+        && words <= MAX_AUTHOR_NAME_WORDS
+        && name.chars().any(char::is_alphabetic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_byline() {
+        assert_eq!(split_byline("By Jane Doe and John Roe"), vec!["Jane Doe", "John Roe"]);
+        assert_eq!(split_byline("Jane Doe, John Roe & Max Mustermann"), vec!["Jane Doe", "John Roe", "Max Mustermann"]);
+        assert_eq!(split_byline("Jane Doe (@janedoe)"), vec!["Jane Doe"]);
+        assert_eq!(split_byline("Jane Doe, Staff Writer"), vec!["Jane Doe"]);
+        assert_eq!(split_byline("John Roe Staff"), vec!["John Roe"]);
+        assert_eq!(split_byline("By Jane Doe | May 5, 2021 at 10:30 AM EST"), vec!["Jane Doe"]);
+        assert_eq!(split_byline("Jane Doe — Updated 2021-05-05"), vec!["Jane Doe"]);
+        assert_eq!(split_byline("Alexander Sandberg"), vec!["Alexander Sandberg"]);
+    }
+
+    #[test]
+    fn test_from_json_ld() {
+        let article: Value = serde_json::from_str(r#"{"author": [
+            {"@type": "Person", "name": "Jane Doe", "url": "https://example.com/jane", "jobTitle": "Editor"},
+            {"@type": "Role", "roleName": "Photographer", "author": {"@type": "Person", "name": "John Roe",
+                "sameAs": ["https://twitter.com/johnroe"]}},
+            "Max Mustermann"
+        ]}"#).unwrap();
+
+        assert_eq!(from_json_ld(&article), vec![
+            Author { name: "Jane Doe".to_string(), url: Some("https://example.com/jane".to_string()), role: Some("Editor".to_string()) },
+            Author { name: "John Roe".to_string(), url: Some("https://twitter.com/johnroe".to_string()), role: Some("Photographer".to_string()) },
+            Author::new("Max Mustermann"),
+        ]);
+
+        // A lone author object is trimmed, nameless ones are skipped
+        let article: Value = serde_json::from_str(r#"{"author": {"name": " Solo "}}"#).unwrap();
+        assert_eq!(from_json_ld(&article), vec![Author::new("Solo")]);
+        let article: Value = serde_json::from_str(r#"{"author": [{"@type": "Person"}, ""]}"#).unwrap();
+        assert_eq!(from_json_ld(&article), Vec::new());
+    }
+
+    #[test]
+    fn test_merge_authors() {
+        let merged = merge_authors(vec![
+            vec![Author::new("Jane Doe")],
+            vec![Author { name: "jane doe".to_string(), url: Some("https://example.com/jane".to_string()), role: None }],
+            vec![Author::new("John Roe")],
+        ]);
+        assert_eq!(merged, vec![
+            Author { name: "Jane Doe".to_string(), url: Some("https://example.com/jane".to_string()), role: None },
+            Author::new("John Roe"),
+        ]);
+    }
+}
@@ -0,0 +1,544 @@
+//! Typed block tree of the extracted article content, for clients that lay
+//! out articles natively instead of rendering HTML
+
+use scraper::{ElementRef, Html, Node};
+use serde::{Deserialize, Serialize};
+use crate::utils::{normalize_whitespace, RENDER_BLOCK_TAGS};
+
+/// Marks `<br>` until spans are cleaned, as source newlines are just whitespace
+const LINE_BREAK: char = '\u{2028}';
+
+/// A block of article content
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Block {
+    Heading { level: u8, spans: Vec<Span> },
+    Paragraph { spans: Vec<Span> },
+    /// Each item is a list of blocks, so items can hold paragraphs and nested lists
+    List { ordered: bool, start: Option<u32>, items: Vec<Vec<Block>> },
+    Quote { blocks: Vec<Block> },
+    Code { lang: Option<String>, text: String },
+    Image {
+        src: String,
+        alt: Option<String>,
+        caption: Option<String>,
+        /// Target of a link wrapping the image
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        link: Option<String>,
+    },
+    Table { rows: Vec<TableRow> },
+    /// Embedded video, audio or frame
+    Embed { kind: EmbedKind, src: String },
+    /// Thematic break (`<hr>`)
+    Rule,
+}
+
+/// A run of text sharing the same formatting
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub text: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub marks: Vec<Mark>,
+    /// Link target, when the span is part of a link
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+}
+
+/// Inline formatting of a span
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mark {
+    Bold,
+    Italic,
+    Underline,
+    Strikethrough,
+    Code,
+    Superscript,
+    Subscript,
+}
+
+/// A table row; header rows are made of `<th>` cells
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableRow {
+    pub header: bool,
+    pub cells: Vec<Vec<Span>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbedKind {
+    Iframe,
+    Video,
+    Audio,
+    Object,
+}
+
+/// Build the block tree of an HTML fragment
+pub fn html_to_blocks(html: &str) -> Vec<Block> {
+    let fragment = Html::parse_fragment(html);
+    let mut builder = BlockBuilder::default();
+    builder.container(&fragment.root_element());
+    builder.finish()
+}
+
+#[derive(Default)]
+struct BlockBuilder {
+    blocks: Vec<Block>,
+    /// Spans of the paragraph being gathered
+    spans: Vec<Span>,
+    /// Gathering the spans of a heading or table cell, where images become
+    /// their alt text instead of splitting the spans
+    inline_only: bool,
+}
+
+impl BlockBuilder {
+    fn finish(mut self) -> Vec<Block> {
+        self.flush();
+        self.blocks
+    }
+
+    /// Blocks of the children of an element, in a builder of their own
+    fn child_blocks(element: &ElementRef) -> Vec<Block> {
+        let mut builder = BlockBuilder::default();
+        builder.container(element);
+        builder.finish()
+    }
+
+    /// Spans of an element's content, for headings and table cells
+    fn child_spans(element: &ElementRef) -> Vec<Span> {
+        let mut builder = BlockBuilder { inline_only: true, ..Default::default() };
+        builder.inline_children(element, &[], None);
+        clean_spans(builder.spans)
+    }
+
+    /// End the paragraph being gathered
+    fn flush(&mut self) {
+        let spans = clean_spans(std::mem::take(&mut self.spans));
+        if !spans.is_empty() {
+            self.blocks.push(Block::Paragraph { spans });
+        }
+    }
+
+    fn container(&mut self, element: &ElementRef) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text, &[], None),
+                Node::Element(_) => {
+                    let child = ElementRef::wrap(child).unwrap();
+                    if RENDER_BLOCK_TAGS.contains(&child.value().name()) {
+                        self.flush();
+                        self.block(&child);
+                    } else {
+                        self.inline(&child, &[], None);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn block(&mut self, element: &ElementRef) {
+        let tag = element.value().name();
+        match tag {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let spans = Self::child_spans(element);
+                if !spans.is_empty() {
+                    self.blocks.push(Block::Heading { level: tag[1..].parse().unwrap(), spans });
+                }
+            }
+            "ul" | "ol" => {
+                let items: Vec<Vec<Block>> = element.children()
+                    .filter_map(ElementRef::wrap)
+                    .filter(|item| item.value().name() == "li")
+                    .map(|item| Self::child_blocks(&item))
+                    .collect();
+                if !items.is_empty() {
+                    let start = element.value().attr("start").and_then(|start| start.trim().parse().ok());
+                    self.blocks.push(Block::List { ordered: tag == "ol", start, items });
+                }
+            }
+            "blockquote" => {
+                let blocks = Self::child_blocks(element);
+                if !blocks.is_empty() {
+                    self.blocks.push(Block::Quote { blocks });
+                }
+            }
+            "pre" => {
+                let text = element.text().collect::<String>();
+                let text = text.strip_prefix('\n').unwrap_or(&text).trim_end().to_string();
+                if !text.is_empty() {
+                    self.blocks.push(Block::Code { lang: code_language(element), text });
+                }
+            }
+            "figure" => self.figure(element),
+            "table" => {
+                let rows = table_rows(element);
+                if !rows.is_empty() {
+                    self.blocks.push(Block::Table { rows });
+                }
+            }
+            "hr" => self.blocks.push(Block::Rule),
+            _ => {
+                self.container(element);
+                self.flush();
+            }
+        }
+    }
+
+    /// A figure becomes its image with the caption attached
+    fn figure(&mut self, figure: &ElementRef) {
+        let caption = figure.children()
+            .filter_map(ElementRef::wrap)
+            .find(|child| child.value().name() == "figcaption")
+            .map(|caption| normalize_whitespace(&caption.text().collect::<String>()))
+            .filter(|caption| !caption.is_empty());
+
+        let mut blocks = Self::child_blocks(figure);
+        // The caption was read into a paragraph of its own
+        if let Some(caption) = &caption {
+            blocks.retain(|block| match block {
+                Block::Paragraph { spans } => &spans_text(spans) != caption,
+                _ => true,
+            });
+        }
+        if let Some(Block::Image { caption: image_caption, .. }) = blocks.iter_mut().find(|block| matches!(block, Block::Image { .. })) {
+            *image_caption = caption;
+        }
+        self.blocks.extend(blocks);
+    }
+
+    fn inline_children(&mut self, element: &ElementRef, marks: &[Mark], link: Option<&str>) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.text(text, marks, link),
+                Node::Element(_) => {
+                    let child = ElementRef::wrap(child).unwrap();
+                    if RENDER_BLOCK_TAGS.contains(&child.value().name()) {
+                        // Blocks in inline context, e.g. in headings or table cells
+                        self.text(" ", marks, link);
+                        self.inline_children(&child, marks, link);
+                        self.text(" ", marks, link);
+                    } else {
+                        self.inline(&child, marks, link);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn inline(&mut self, element: &ElementRef, marks: &[Mark], link: Option<&str>) {
+        let mark = match element.value().name() {
+            "strong" | "b" => Some(Mark::Bold),
+            "em" | "i" | "cite" => Some(Mark::Italic),
+            "u" | "ins" => Some(Mark::Underline),
+            "del" | "s" | "strike" => Some(Mark::Strikethrough),
+            "code" | "kbd" | "samp" => Some(Mark::Code),
+            "sup" => Some(Mark::Superscript),
+            "sub" => Some(Mark::Subscript),
+            "br" => {
+                self.text(&LINE_BREAK.to_string(), marks, link);
+                return;
+            }
+            "img" if self.inline_only => {
+                if let Some(alt) = attr(element, "alt") {
+                    self.text(&format!(" {} ", alt), marks, link);
+                }
+                return;
+            }
+            "img" => {
+                if let Some(src) = attr(element, "src").or_else(|| attr(element, "data-src")) {
+                    self.flush();
+                    self.blocks.push(Block::Image {
+                        src: src.to_string(),
+                        alt: attr(element, "alt").map(normalize_whitespace),
+                        caption: None,
+                        link: link.map(str::to_string),
+                    });
+                }
+                return;
+            }
+            "iframe" | "video" | "audio" | "embed" | "object" => {
+                if let Some(embed) = embed_block(element).filter(|_| !self.inline_only) {
+                    self.flush();
+                    self.blocks.push(embed);
+                }
+                return;
+            }
+            "script" | "style" | "noscript" | "template" => return,
+            "a" => {
+                let href = attr(element, "href");
+                self.inline_children(element, marks, href.or(link));
+                return;
+            }
+            _ => None,
+        };
+
+        match mark {
+            Some(mark) if !marks.contains(&mark) => {
+                let mut marks = marks.to_vec();
+                marks.push(mark);
+                marks.sort();
+                self.inline_children(element, &marks, link);
+            }
+            _ => self.inline_children(element, marks, link),
+        }
+    }
+
+    fn text(&mut self, text: &str, marks: &[Mark], link: Option<&str>) {
+        match self.spans.last_mut() {
+            Some(last) if last.marks == marks && last.link.as_deref() == link => last.text.push_str(text),
+            _ => self.spans.push(Span {
+                text: text.to_string(),
+                marks: marks.to_vec(),
+                link: link.map(str::to_string),
+            }),
+        }
+    }
+}
+
+/// Collapse whitespace across span boundaries, turn `<br>` into newlines and
+/// drop spans left empty
+fn clean_spans(spans: Vec<Span>) -> Vec<Span> {
+    let mut cleaned: Vec<Span> = Vec::new();
+    // Start of the paragraph or right after a space or line break
+    let mut at_break = true;
+
+    for mut span in spans {
+        let mut text = String::with_capacity(span.text.len());
+        for c in span.text.chars() {
+            if c == LINE_BREAK {
+                // Spaces never end a line
+                if text.ends_with(' ') {
+                    text.pop();
+                } else if let Some(previous) = cleaned.last_mut().filter(|_| text.is_empty()) {
+                    if previous.text.ends_with(' ') {
+                        previous.text.pop();
+                    }
+                }
+                text.push('\n');
+                at_break = true;
+            } else if c.is_whitespace() {
+                if !at_break {
+                    text.push(' ');
+                    at_break = true;
+                }
+            } else {
+                text.push(c);
+                at_break = false;
+            }
+        }
+        span.text = text;
+        if !span.text.is_empty() {
+            cleaned.push(span);
+        }
+    }
+
+    // Trim the end of the paragraph
+    while let Some(last) = cleaned.last_mut() {
+        let trimmed_len = last.text.trim_end().len();
+        last.text.truncate(trimmed_len);
+        if last.text.is_empty() {
+            cleaned.pop();
+        } else {
+            break;
+        }
+    }
+    // Leading line breaks carry no meaning
+    while let Some(first) = cleaned.first_mut() {
+        let trimmed = first.text.trim_start().to_string();
+        if trimmed.is_empty() {
+            cleaned.remove(0);
+        } else {
+            first.text = trimmed;
+            break;
+        }
+    }
+
+    cleaned
+}
+
+fn spans_text(spans: &[Span]) -> String {
+    normalize_whitespace(&spans.iter().map(|span| span.text.as_str()).collect::<String>())
+}
+
+/// Render spans with the escaping, mark delimiters and links of a markup
+/// language. Whitespace at the edges of a span is kept outside the
+/// delimiters, which most lightweight markups need.
+pub(crate) fn render_spans(
+    spans: &[Span],
+    escape: impl Fn(&str) -> String,
+    mark: impl Fn(Mark, &str) -> String,
+    link: impl Fn(&str, &str) -> String,
+) -> String {
+    let mut output = String::new();
+    for span in spans {
+        let text = span.text.trim();
+        if text.is_empty() {
+            output.push_str(&escape(&span.text));
+            continue;
+        }
+        let start = span.text.find(text).unwrap_or(0);
+        let mut rendered = escape(text);
+        for &span_mark in span.marks.iter().rev() {
+            rendered = mark(span_mark, &rendered);
+        }
+        if let Some(target) = &span.link {
+            rendered = link(target, &rendered);
+        }
+        output.push_str(&escape(&span.text[..start]));
+        output.push_str(&rendered);
+        output.push_str(&escape(&span.text[start + text.len()..]));
+    }
+    output
+}
+
+/// Spans with their line breaks turned into spaces, for headings and table
+/// cells that have to stay on one line
+pub(crate) fn single_line(spans: &[Span]) -> Vec<Span> {
+    spans.iter()
+        .map(|span| Span { text: span.text.replace('\n', " "), ..span.clone() })
+        .collect()
+}
+
+fn table_rows(table: &ElementRef) -> Vec<TableRow> {
+    table.descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|row| row.value().name() == "tr")
+        .filter(|row| {
+            // Skip rows of nested tables
+            row.ancestors().filter_map(ElementRef::wrap)
+                .find(|ancestor| ancestor.value().name() == "table")
+                .map(|owner| owner.id()) == Some(table.id())
+        })
+        .map(|row| {
+            let cells: Vec<ElementRef> = row.children()
+                .filter_map(ElementRef::wrap)
+                .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+                .collect();
+            TableRow {
+                header: !cells.is_empty() && cells.iter().all(|cell| cell.value().name() == "th"),
+                cells: cells.iter().map(BlockBuilder::child_spans).collect(),
+            }
+        })
+        .filter(|row| !row.cells.is_empty())
+        .collect()
+}
+
+fn embed_block(element: &ElementRef) -> Option<Block> {
+    let kind = match element.value().name() {
+        "iframe" | "embed" => EmbedKind::Iframe,
+        "video" => EmbedKind::Video,
+        "audio" => EmbedKind::Audio,
+        _ => EmbedKind::Object,
+    };
+    let src = attr(element, "src")
+        .or_else(|| attr(element, "data"))
+        .or_else(|| element.children()
+            .filter_map(ElementRef::wrap)
+            .find(|child| child.value().name() == "source")
+            .and_then(|source| attr(&source, "src")))?;
+    Some(Block::Embed { kind, src: src.to_string() })
+}
+
+fn code_language(pre: &ElementRef) -> Option<String> {
+    let code = pre.children()
+        .filter_map(ElementRef::wrap)
+        .find(|child| child.value().name() == "code");
+    [Some(*pre), code].into_iter()
+        .flatten()
+        .flat_map(|element| element.value().classes().collect::<Vec<_>>())
+        .find_map(|class| class.strip_prefix("language-").or_else(|| class.strip_prefix("lang-")))
+        .map(str::to_string)
+}
+
+fn attr<'a>(element: &ElementRef<'a>, name: &str) -> Option<&'a str> {
+    element.value().attr(name).map(str::trim).filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(text: &str, marks: &[Mark], link: Option<&str>) -> Span {
+        Span { text: text.to_string(), marks: marks.to_vec(), link: link.map(str::to_string) }
+    }
+
+    #[test]
+    fn test_paragraph_spans() {
+        let blocks = html_to_blocks(r#"<p>  Plain <strong>bold <em>both</em></strong> <a href="/x">a <code>link</code></a><br>
+            next line </p>"#);
+        assert_eq!(blocks, vec![Block::Paragraph { spans: vec![
+            span("Plain ", &[], None),
+            span("bold ", &[Mark::Bold], None),
+            span("both", &[Mark::Bold, Mark::Italic], None),
+            span(" ", &[], None),
+            span("a ", &[], Some("/x")),
+            span("link", &[Mark::Code], Some("/x")),
+            span("\nnext line", &[], None),
+        ]}]);
+    }
+
+    #[test]
+    fn test_block_types() {
+        let blocks = html_to_blocks(r#"<h2>Title</h2>
+            <ol start="2"><li>One<ul><li>Nested</li></ul></li></ol>
+            <blockquote><p>Quote</p></blockquote>
+            <pre><code class="language-rust">let x = 1;</code></pre>
+            <figure><img src="a.jpg" alt="Alt"><figcaption>A caption</figcaption></figure>
+            <p>Before <img src="b.jpg"> after</p>
+            <iframe src="https://www.youtube.com/embed/x"></iframe>
+            <video><source src="v.mp4"></video>
+            <table><tr><th>H</th></tr><tr><td>C</td></tr></table><hr>"#);
+
+        let text = |text: &str| vec![span(text, &[], None)];
+        assert_eq!(blocks, vec![
+            Block::Heading { level: 2, spans: text("Title") },
+            Block::List { ordered: true, start: Some(2), items: vec![vec![
+                Block::Paragraph { spans: text("One") },
+                Block::List { ordered: false, start: None, items: vec![vec![Block::Paragraph { spans: text("Nested") }]] },
+            ]]},
+            Block::Quote { blocks: vec![Block::Paragraph { spans: text("Quote") }] },
+            Block::Code { lang: Some("rust".to_string()), text: "let x = 1;".to_string() },
+            Block::Image { src: "a.jpg".to_string(), alt: Some("Alt".to_string()), caption: Some("A caption".to_string()), link: None },
+            Block::Paragraph { spans: text("Before") },
+            Block::Image { src: "b.jpg".to_string(), alt: None, caption: None, link: None },
+            Block::Paragraph { spans: text("after") },
+            Block::Embed { kind: EmbedKind::Iframe, src: "https://www.youtube.com/embed/x".to_string() },
+            Block::Embed { kind: EmbedKind::Video, src: "v.mp4".to_string() },
+            Block::Table { rows: vec![
+                TableRow { header: true, cells: vec![text("H")] },
+                TableRow { header: false, cells: vec![text("C")] },
+            ]},
+            Block::Rule,
+        ]);
+    }
+
+    #[test]
+    fn test_inline_images() {
+        let blocks = html_to_blocks(r#"<h2>Title <img src="icon.png" alt="Icon"></h2>
+            <table><tr><td>Cell <img src="c.png" alt="pic"> after</td><td><img src="d.png"> only</td></tr></table>
+            <p><a href="https://example.com/big.jpg"><img src="small.jpg" alt="Small"></a></p>"#);
+
+        let text = |text: &str| vec![span(text, &[], None)];
+        assert_eq!(blocks, vec![
+            Block::Heading { level: 2, spans: text("Title Icon") },
+            Block::Table { rows: vec![TableRow { header: false, cells: vec![text("Cell pic after"), text("only")] }] },
+            Block::Image {
+                src: "small.jpg".to_string(),
+                alt: Some("Small".to_string()),
+                caption: None,
+                link: Some("https://example.com/big.jpg".to_string()),
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_serialization() {
+        let blocks = html_to_blocks("<h1>Hi <em>there</em></h1>");
+        assert_eq!(
+            serde_json::to_string(&blocks).unwrap(),
+            r#"[{"type":"heading","level":1,"spans":[{"text":"Hi "},{"text":"there","marks":["italic"]}]}]"#
+        );
+    }
+}
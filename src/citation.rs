@@ -0,0 +1,373 @@
+//! Scholarly citation metadata (Highwire Press `citation_*` and PRISM tags)
+//! and its BibTeX and CSL-JSON exports
+
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use crate::utils::{normalize_whitespace, unescape_html_entities};
+
+/// Bibliographic metadata of a scholarly article
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Citation {
+    pub title: Option<String>,
+    /// Author names as written, e.g. "Doe, Jane" or "Jane Doe"
+    pub authors: Vec<String>,
+    /// DOI without the `doi:` or resolver prefix
+    pub doi: Option<String>,
+    pub journal_title: Option<String>,
+    pub conference_title: Option<String>,
+    pub publisher: Option<String>,
+    /// Publication date as written, e.g. `2021/05/05`
+    pub publication_date: Option<String>,
+    pub volume: Option<String>,
+    pub issue: Option<String>,
+    pub first_page: Option<String>,
+    pub last_page: Option<String>,
+    pub issn: Option<String>,
+    pub isbn: Option<String>,
+    /// Landing page of the article
+    pub url: Option<String>,
+    pub pdf_url: Option<String>,
+    pub language: Option<String>,
+    pub keywords: Vec<String>,
+}
+
+/// Read the `citation_*` and `prism.*` tags of a document. Returns `None` for
+/// pages without any.
+pub fn extract_citation(document: &Html) -> Option<Citation> {
+    let meta_selector = Selector::parse("meta[content]").unwrap();
+    let mut citation = Citation::default();
+    let mut found = false;
+
+    for meta in document.select(&meta_selector) {
+        let key = match meta.value().attr("name").or_else(|| meta.value().attr("property")) {
+            // Stored like `get_article_metadata` does, so `prism.issn` is `prism:issn`
+            Some(key) => key.trim().to_lowercase().replace('.', ":"),
+            None => continue,
+        };
+        let value = normalize_whitespace(&unescape_html_entities(meta.value().attr("content").unwrap_or("")));
+        if value.is_empty() {
+            continue;
+        }
+
+        // The first tag wins for single-valued fields
+        let set = |field: &mut Option<String>| {
+            field.get_or_insert_with(|| value.clone());
+        };
+        match key.as_str() {
+            "citation_title" => set(&mut citation.title),
+            "citation_author" => citation.authors.push(value.clone()),
+            "citation_authors" => citation.authors.extend(split_list(&value)),
+            "citation_doi" | "prism:doi" => {
+                citation.doi.get_or_insert_with(|| clean_doi(&value));
+            }
+            "citation_journal_title" | "prism:publicationname" => set(&mut citation.journal_title),
+            "citation_conference_title" => set(&mut citation.conference_title),
+            "citation_publisher" => set(&mut citation.publisher),
+            // Dublin Core tags are common outside scholarly pages, so they
+            // fill in the publisher without making the page a citation
+            "dc:publisher" => {
+                set(&mut citation.publisher);
+                continue;
+            }
+            "citation_publication_date" | "citation_date" | "citation_online_date"
+            | "prism:publicationdate" | "prism:coverdate" => set(&mut citation.publication_date),
+            "citation_volume" | "prism:volume" => set(&mut citation.volume),
+            "citation_issue" | "prism:number" => set(&mut citation.issue),
+            "citation_firstpage" | "prism:startingpage" => set(&mut citation.first_page),
+            "citation_lastpage" | "prism:endingpage" => set(&mut citation.last_page),
+            "citation_issn" | "prism:issn" | "prism:eissn" => set(&mut citation.issn),
+            "citation_isbn" | "prism:isbn" => set(&mut citation.isbn),
+            "citation_abstract_html_url" | "citation_fulltext_html_url" | "citation_public_url"
+            | "prism:url" => set(&mut citation.url),
+            "citation_pdf_url" => set(&mut citation.pdf_url),
+            "citation_language" => set(&mut citation.language),
+            "citation_keywords" | "prism:keyword" => citation.keywords.extend(split_list(&value)),
+            _ => continue,
+        }
+        found = true;
+    }
+
+    if found { Some(citation) } else { None }
+}
+
+impl Citation {
+    /// Export as a BibTeX entry
+    pub fn to_bibtex(&self) -> String {
+        let entry_type = if self.journal_title.is_some() {
+            "article"
+        } else if self.conference_title.is_some() {
+            "inproceedings"
+        } else if self.isbn.is_some() {
+            "book"
+        } else {
+            "misc"
+        };
+        let (year, month, _) = self.date_parts();
+
+        let mut fields: Vec<(&str, String)> = Vec::new();
+        let mut add = |name, value: Option<&String>| {
+            if let Some(value) = value {
+                fields.push((name, value.clone()));
+            }
+        };
+        add("title", self.title.as_ref());
+        add("author", Some(&self.authors.join(" and ")).filter(|authors| !authors.is_empty()));
+        add("journal", self.journal_title.as_ref());
+        add("booktitle", self.conference_title.as_ref());
+        add("publisher", self.publisher.as_ref());
+        add("year", year.map(|year| year.to_string()).as_ref());
+        add("month", month.map(|month| month.to_string()).as_ref());
+        add("volume", self.volume.as_ref());
+        add("number", self.issue.as_ref());
+        add("pages", self.pages("--").as_ref());
+        add("doi", self.doi.as_ref());
+        add("issn", self.issn.as_ref());
+        add("isbn", self.isbn.as_ref());
+        add("url", self.url.as_ref().or(self.pdf_url.as_ref()));
+        add("language", self.language.as_ref());
+        add("keywords", Some(&self.keywords.join(", ")).filter(|keywords| !keywords.is_empty()));
+
+        let mut bibtex = format!("@{}{{{},\n", entry_type, self.citation_key());
+        for (name, value) in fields {
+            // DOIs and URLs are read verbatim, only their braces have to be kept balanced
+            let value = match name {
+                "doi" | "url" => value.replace('{', "%7B").replace('}', "%7D"),
+                _ => escape_bibtex(&value),
+            };
+            bibtex.push_str(&format!("  {} = {{{}}},\n", name, value));
+        }
+        bibtex.push_str("}\n");
+        bibtex
+    }
+
+    /// Export as a CSL-JSON item, as read by Zotero, Pandoc and citeproc
+    pub fn to_csl_json(&self) -> Value {
+        let item_type = if self.journal_title.is_some() {
+            "article-journal"
+        } else if self.conference_title.is_some() {
+            "paper-conference"
+        } else if self.isbn.is_some() {
+            "book"
+        } else {
+            "article"
+        };
+
+        let mut item = Map::new();
+        item.insert("id".to_string(), json!(self.citation_key()));
+        item.insert("type".to_string(), json!(item_type));
+        let mut add = |name: &str, value: Option<Value>| {
+            if let Some(value) = value {
+                item.insert(name.to_string(), value);
+            }
+        };
+        add("title", self.title.as_ref().map(|title| json!(title)));
+        if !self.authors.is_empty() {
+            add("author", Some(self.authors.iter().map(|author| csl_name(author)).collect()));
+        }
+        add("container-title", self.journal_title.as_ref().or(self.conference_title.as_ref()).map(|title| json!(title)));
+        add("publisher", self.publisher.as_ref().map(|publisher| json!(publisher)));
+        add("issued", match self.date_parts() {
+            (Some(year), month, day) => {
+                let parts: Vec<u32> = [Some(year), month, day].into_iter().map_while(|part| part).collect();
+                Some(json!({ "date-parts": [parts] }))
+            }
+            _ => None,
+        });
+        add("volume", self.volume.as_ref().map(|volume| json!(volume)));
+        add("issue", self.issue.as_ref().map(|issue| json!(issue)));
+        add("page", self.pages("-").map(|pages| json!(pages)));
+        add("DOI", self.doi.as_ref().map(|doi| json!(doi)));
+        add("ISSN", self.issn.as_ref().map(|issn| json!(issn)));
+        add("ISBN", self.isbn.as_ref().map(|isbn| json!(isbn)));
+        add("URL", self.url.as_ref().or(self.pdf_url.as_ref()).map(|url| json!(url)));
+        add("language", self.language.as_ref().map(|language| json!(language)));
+        if !self.keywords.is_empty() {
+            add("keyword", Some(json!(self.keywords.join(", "))));
+        }
+
+        Value::Object(item)
+    }
+
+    /// Key like `doe2021quantum` from the first author, year and title word
+    pub fn citation_key(&self) -> String {
+        let family = self.authors.first().map(|author| split_name(author).0).unwrap_or_default();
+        let year = self.date_parts().0.map(|year| year.to_string()).unwrap_or_default();
+        let title_word = self.title.as_deref().unwrap_or("")
+            .split_whitespace()
+            .map(|word| word.chars().filter(|c| c.is_alphanumeric()).collect::<String>())
+            .find(|word| word.chars().count() > 3)
+            .unwrap_or_default();
+
+        let key: String = format!("{}{}{}", family, year, title_word)
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect();
+        if key.is_empty() { "citation".to_string() } else { key }
+    }
+
+    fn pages(&self, separator: &str) -> Option<String> {
+        match (&self.first_page, &self.last_page) {
+            (Some(first), Some(last)) => Some(format!("{}{}{}", first, separator, last)),
+            (Some(first), None) => Some(first.clone()),
+            _ => None,
+        }
+    }
+
+    /// Year, month and day of dates like `2021/05/05`, `2021-05` or `2021`
+    fn date_parts(&self) -> (Option<u32>, Option<u32>, Option<u32>) {
+        let date = match &self.publication_date {
+            Some(date) => date,
+            None => return (None, None, None),
+        };
+        let mut parts = date.split(['/', '-', ' ', 'T'])
+            .take(3)
+            .map(|part| part.parse::<u32>().ok());
+        let year = parts.next().flatten().filter(|year| *year >= 1000);
+        let month = parts.next().flatten().filter(|month| (1..=12).contains(month));
+        let day = month.and(parts.next().flatten()).filter(|day| (1..=31).contains(day));
+        (year, month, day)
+    }
+}
+
+/// Split "Doe, Jane" or "Jane Doe" into family and given names
+fn split_name(name: &str) -> (String, String) {
+    match name.split_once(',') {
+        Some((family, given)) => (family.trim().to_string(), given.trim().to_string()),
+        None => match name.trim().rsplit_once(' ') {
+            Some((given, family)) => (family.to_string(), given.trim().to_string()),
+            None => (name.trim().to_string(), String::new()),
+        },
+    }
+}
+
+fn csl_name(name: &str) -> Value {
+    match split_name(name) {
+        (family, given) if given.is_empty() => json!({ "literal": family }),
+        (family, given) => json!({ "family": family, "given": given }),
+    }
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value.split(';')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn clean_doi(doi: &str) -> String {
+    let doi = doi.trim();
+    ["https://doi.org/", "http://doi.org/", "https://dx.doi.org/", "http://dx.doi.org/", "doi:"]
+        .iter()
+        .find_map(|prefix| doi.strip_prefix(prefix))
+        .unwrap_or(doi)
+        .trim()
+        .to_string()
+}
+
+fn escape_bibtex(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHOLARLY_PAGE: &str = r#"<html><head>
+        <meta name="citation_title" content="Quantum Effects in Rust &amp; Other Languages">
+        <meta name="citation_author" content="Doe, Jane">
+        <meta name="citation_author" content="John Roe">
+        <meta name="citation_publication_date" content="2021/05/05">
+        <meta name="citation_journal_title" content="Journal of Examples">
+        <meta name="citation_volume" content="12">
+        <meta name="citation_issue" content="3">
+        <meta name="citation_firstpage" content="101">
+        <meta name="citation_lastpage" content="110">
+        <meta name="citation_doi" content="doi:10.1234/example.5678">
+        <meta name="citation_pdf_url" content="https://example.com/paper.pdf">
+        <meta name="PRISM.issn" content="1234-5678">
+        <meta name="prism.url" content="https://example.com/paper">
+        </head></html>"#;
+
+    #[test]
+    fn test_extract_citation() {
+        let citation = extract_citation(&Html::parse_document(SCHOLARLY_PAGE)).unwrap();
+        assert_eq!(citation.title, Some("Quantum Effects in Rust & Other Languages".to_string()));
+        assert_eq!(citation.authors, vec!["Doe, Jane", "John Roe"]);
+        assert_eq!(citation.doi, Some("10.1234/example.5678".to_string()));
+        assert_eq!(citation.issn, Some("1234-5678".to_string()));
+        assert_eq!(citation.url, Some("https://example.com/paper".to_string()));
+        assert_eq!(citation.citation_key(), "doe2021quantum");
+
+        let document = Html::parse_document(r#"<html><head><meta name="description" content="x"></head></html>"#);
+        assert_eq!(extract_citation(&document), None);
+        let document = Html::parse_document(r#"<html><head><meta name="DC.publisher" content="ACME"></head></html>"#);
+        assert_eq!(extract_citation(&document), None);
+    }
+
+    #[test]
+    fn test_to_bibtex() {
+        let citation = extract_citation(&Html::parse_document(SCHOLARLY_PAGE)).unwrap();
+        assert_eq!(citation.to_bibtex(), "@article{doe2021quantum,
+  title = {Quantum Effects in Rust \\& Other Languages},
+  author = {Doe, Jane and John Roe},
+  journal = {Journal of Examples},
+  year = {2021},
+  month = {5},
+  volume = {12},
+  number = {3},
+  pages = {101--110},
+  doi = {10.1234/example.5678},
+  issn = {1234-5678},
+  url = {https://example.com/paper},
+}
+");
+    }
+
+    #[test]
+    fn test_bibtex_escaping() {
+        let citation = Citation {
+            title: Some(r"50% of C:\temp ~ x^2 & a_b".to_string()),
+            doi: Some("10.1000/a_b".to_string()),
+            url: Some("https://x.org/a_b%20c#d".to_string()),
+            ..Default::default()
+        };
+        let bibtex = citation.to_bibtex();
+        assert!(bibtex.contains(r"title = {50\% of C:\textbackslash{}temp \textasciitilde{} x\textasciicircum{}2 \& a\_b},"));
+        assert!(bibtex.contains("doi = {10.1000/a_b},"));
+        assert!(bibtex.contains("url = {https://x.org/a_b%20c#d},"));
+    }
+
+    #[test]
+    fn test_to_csl_json() {
+        let citation = extract_citation(&Html::parse_document(SCHOLARLY_PAGE)).unwrap();
+        let item = citation.to_csl_json();
+        assert_eq!(item["type"], "article-journal");
+        assert_eq!(item["author"], json!([{"family": "Doe", "given": "Jane"}, {"family": "Roe", "given": "John"}]));
+        assert_eq!(item["container-title"], "Journal of Examples");
+        assert_eq!(item["issued"], json!({"date-parts": [[2021, 5, 5]]}));
+        assert_eq!(item["page"], "101-110");
+        assert_eq!(item["DOI"], "10.1234/example.5678");
+
+        let item = Citation { title: Some("Untitled".to_string()), publication_date: Some("2020".to_string()), ..Default::default() }
+            .to_csl_json();
+        assert_eq!(item["type"], "article");
+        assert_eq!(item["issued"], json!({"date-parts": [[2020]]}));
+        assert!(item.get("author").is_none());
+    }
+}
@@ -0,0 +1,503 @@
+//! EPUB 3 export of one or more articles, for reading on e-readers
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{Seek, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use scraper::{ElementRef, Html, Node};
+use thiserror::Error;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::Article;
+
+/// Elements serialized as self-closing tags
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+/// Elements dropped with their content, as EPUB reading systems need them
+/// declared in the manifest
+const DROPPED_TAGS: &[&str] = &["script", "noscript", "template", "iframe", "object", "embed", "source"];
+
+/// Image types reading systems must support, by file extension
+const IMAGE_MEDIA_TYPES: &[(&str, &str)] = &[
+    ("gif", "image/gif"),
+    ("jpg", "image/jpeg"),
+    ("jpeg", "image/jpeg"),
+    ("png", "image/png"),
+    ("svg", "image/svg+xml"),
+    ("webp", "image/webp"),
+];
+
+/// Errors that can occur while writing an EPUB
+#[derive(Error, Debug)]
+pub enum EpubError {
+    #[error("No articles to write")]
+    NoArticles,
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+}
+
+/// Options for `write_epub`
+#[derive(Debug, Clone, Default)]
+pub struct EpubOptions {
+    /// Book title; defaults to the title of a single article
+    pub title: Option<String>,
+    /// Book author; defaults to the distinct bylines of the articles
+    pub author: Option<String>,
+    /// Book language; defaults to the language of the first article, then `en`
+    pub lang: Option<String>,
+    /// Unique identifier of the book; defaults to the canonical URL of a
+    /// single article, then a hash of the content
+    pub identifier: Option<String>,
+    /// Directory holding the article images, looked up by the file name of
+    /// their URL. Images not found there are left out, as EPUB does not
+    /// allow remote images.
+    pub resource_dir: Option<PathBuf>,
+}
+
+/// An image copied into the book
+struct EpubImage {
+    id: String,
+    href: String,
+    media_type: &'static str,
+    path: PathBuf,
+}
+
+/// Write the articles as an EPUB 3 book with one chapter per article
+pub fn write_epub<W: Write + Seek>(articles: &[Article], options: &EpubOptions, writer: W) -> Result<(), EpubError> {
+    if articles.is_empty() {
+        return Err(EpubError::NoArticles);
+    }
+
+    let lang = options.lang.clone()
+        .or_else(|| articles[0].lang.clone())
+        .filter(|lang| !lang.trim().is_empty())
+        .unwrap_or_else(|| "en".to_string());
+
+    let mut images = Vec::new();
+    let chapters: Vec<String> = articles.iter()
+        .enumerate()
+        .map(|(i, article)| chapter_document(article, i, &lang, options.resource_dir.as_deref(), &mut images))
+        .collect();
+
+    let mut zip = ZipWriter::new(writer);
+
+    // The mimetype comes first and uncompressed so the file can be identified
+    zip.start_file("mimetype", FileOptions::default().compression_method(CompressionMethod::Stored))?;
+    zip.write_all(b"application/epub+zip")?;
+
+    let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file("META-INF/container.xml", deflated)?;
+    zip.write_all(CONTAINER_XML.as_bytes())?;
+
+    zip.start_file("OEBPS/content.opf", deflated)?;
+    zip.write_all(package_document(articles, options, &lang, &images).as_bytes())?;
+
+    zip.start_file("OEBPS/nav.xhtml", deflated)?;
+    zip.write_all(nav_document(articles, &book_title(articles, options), &lang).as_bytes())?;
+
+    for (i, chapter) in chapters.iter().enumerate() {
+        zip.start_file(format!("OEBPS/{}", chapter_href(i)), deflated)?;
+        zip.write_all(chapter.as_bytes())?;
+    }
+
+    for image in &images {
+        // Images are compressed already
+        zip.start_file(format!("OEBPS/{}", image.href), FileOptions::default().compression_method(CompressionMethod::Stored))?;
+        zip.write_all(&fs::read(&image.path)?)?;
+    }
+
+    zip.finish()?;
+    Ok(())
+}
+
+const CONTAINER_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+fn chapter_href(index: usize) -> String {
+    format!("chapter-{}.xhtml", index + 1)
+}
+
+fn article_title(article: &Article, index: usize) -> String {
+    article.title.clone()
+        .filter(|title| !title.trim().is_empty())
+        .unwrap_or_else(|| format!("Chapter {}", index + 1))
+}
+
+fn book_title(articles: &[Article], options: &EpubOptions) -> String {
+    match (&options.title, articles) {
+        (Some(title), _) => title.clone(),
+        (None, [article]) => article_title(article, 0),
+        _ => "Articles".to_string(),
+    }
+}
+
+fn package_document(articles: &[Article], options: &EpubOptions, lang: &str, images: &[EpubImage]) -> String {
+    let identifier = options.identifier.clone()
+        .or_else(|| match articles {
+            [article] => article.links.canonical.clone(),
+            _ => None,
+        })
+        .unwrap_or_else(|| {
+            let mut hasher = DefaultHasher::new();
+            for article in articles {
+                article.title.hash(&mut hasher);
+                article.content.hash(&mut hasher);
+            }
+            format!("urn:readability:{:016x}", hasher.finish())
+        });
+
+    let creators: Vec<String> = match &options.author {
+        Some(author) => vec![author.clone()],
+        None => {
+            let mut creators: Vec<String> = Vec::new();
+            for byline in articles.iter().filter_map(|article| article.byline.as_deref()) {
+                let byline = byline.trim().to_string();
+                if !byline.is_empty() && !creators.contains(&byline) {
+                    creators.push(byline);
+                }
+            }
+            creators
+        }
+    };
+
+    let mut metadata = vec![
+        format!(r#"<dc:identifier id="book-id">{}</dc:identifier>"#, escape_xml(&identifier)),
+        format!("<dc:title>{}</dc:title>", escape_xml(&book_title(articles, options))),
+        format!("<dc:language>{}</dc:language>", escape_xml(lang)),
+    ];
+    for creator in &creators {
+        metadata.push(format!("<dc:creator>{}</dc:creator>", escape_xml(creator)));
+    }
+    if let [Article { published_at: Some(published_at), .. }] = articles {
+        metadata.push(format!("<dc:date>{}</dc:date>", published_at.to_rfc3339()));
+    }
+    metadata.push(format!(
+        r#"<meta property="dcterms:modified">{}</meta>"#,
+        Utc::now().format("%Y-%m-%dT%H:%M:%SZ")
+    ));
+
+    let mut manifest = vec![
+        r#"<item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>"#.to_string(),
+    ];
+    let mut spine = Vec::new();
+    for i in 0..articles.len() {
+        manifest.push(format!(
+            r#"<item id="chapter-{}" href="{}" media-type="application/xhtml+xml"/>"#,
+            i + 1,
+            chapter_href(i)
+        ));
+        spine.push(format!(r#"<itemref idref="chapter-{}"/>"#, i + 1));
+    }
+    for image in images {
+        manifest.push(format!(
+            r#"<item id="{}" href="{}" media-type="{}"/>"#,
+            image.id, image.href, image.media_type
+        ));
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\" xml:lang=\"{}\">\n\
+         \x20 <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n    {}\n  </metadata>\n\
+         \x20 <manifest>\n    {}\n  </manifest>\n\
+         \x20 <spine>\n    {}\n  </spine>\n\
+         </package>\n",
+        escape_xml(lang),
+        metadata.join("\n    "),
+        manifest.join("\n    "),
+        spine.join("\n    ")
+    )
+}
+
+fn nav_document(articles: &[Article], title: &str, lang: &str) -> String {
+    let entries: Vec<String> = articles.iter().enumerate()
+        .map(|(i, article)| format!(
+            r#"<li><a href="{}">{}</a></li>"#,
+            chapter_href(i),
+            escape_xml(&article_title(article, i))
+        ))
+        .collect();
+
+    xhtml_document(
+        title,
+        lang,
+        None,
+        &format!(
+            "<nav epub:type=\"toc\" id=\"toc\">\n<h1>{}</h1>\n<ol>\n{}\n</ol>\n</nav>",
+            escape_xml(title),
+            entries.join("\n")
+        ),
+    )
+}
+
+fn chapter_document(
+    article: &Article,
+    index: usize,
+    lang: &str,
+    resource_dir: Option<&Path>,
+    images: &mut Vec<EpubImage>,
+) -> String {
+    let title = article_title(article, index);
+    let lang = article.lang.as_deref().filter(|lang| !lang.trim().is_empty()).unwrap_or(lang);
+
+    let mut body = format!("<article>\n<h1>{}</h1>\n", escape_xml(&title));
+    if let Some(byline) = article.byline.as_deref().filter(|byline| !byline.trim().is_empty()) {
+        body.push_str(&format!("<p class=\"byline\">By {}</p>\n", escape_xml(byline)));
+    }
+    if let Some(content) = &article.content {
+        let fragment = Html::parse_fragment(content);
+        let mut serializer = XhtmlSerializer { output: String::new(), resource_dir, images };
+        serializer.write_children(&fragment.root_element());
+        body.push_str(&serializer.output);
+        body.push('\n');
+    }
+    body.push_str("</article>");
+
+    xhtml_document(&title, lang, article.dir.as_deref(), &body)
+}
+
+fn xhtml_document(title: &str, lang: &str, dir: Option<&str>, body: &str) -> String {
+    let dir = match dir {
+        Some(dir @ ("ltr" | "rtl")) => format!(" dir=\"{}\"", dir),
+        _ => String::new(),
+    };
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE html>\n\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" xml:lang=\"{lang}\" lang=\"{lang}\"{dir}>\n\
+         <head>\n<meta charset=\"UTF-8\"/>\n<title>{title}</title>\n</head>\n\
+         <body>\n{body}\n</body>\n\
+         </html>\n",
+        lang = escape_xml(lang),
+        dir = dir,
+        title = escape_xml(title),
+        body = body
+    )
+}
+
+/// Serializes parsed HTML as well-formed XHTML, embedding local images
+struct XhtmlSerializer<'a> {
+    output: String,
+    resource_dir: Option<&'a Path>,
+    images: &'a mut Vec<EpubImage>,
+}
+
+impl XhtmlSerializer<'_> {
+    fn write_children(&mut self, parent: &ElementRef) {
+        for child in parent.children() {
+            match child.value() {
+                Node::Text(text) => self.output.push_str(&escape_xml(text)),
+                Node::Element(_) => self.write_element(&ElementRef::wrap(child).unwrap()),
+                _ => {}
+            }
+        }
+    }
+
+    fn write_element(&mut self, node: &ElementRef) {
+        let element = node.value();
+        let name = element.name();
+        if DROPPED_TAGS.contains(&name) {
+            return;
+        }
+        // Media would have to be declared as remote resources, so they
+        // become links to their source
+        if matches!(name, "video" | "audio") {
+            if let Some(src) = media_source(node) {
+                let label = if name == "video" { "Video" } else { "Audio" };
+                self.output.push_str(&format!("<a href=\"{}\">{}</a>", escape_xml(src), label));
+            }
+            return;
+        }
+        // Elements XML cannot name keep only their content
+        if !is_xml_name(name) {
+            self.write_children(node);
+            return;
+        }
+
+        let mut attributes = Vec::new();
+        for (attr, value) in element.attrs() {
+            if !is_xml_name(attr) || attr.starts_with("on") || attr == "srcset" || attr == "sizes" {
+                continue;
+            }
+            let value = if name == "img" && attr == "src" {
+                match self.embed_image(value) {
+                    Some(href) => href,
+                    None => return,
+                }
+            } else {
+                value.to_string()
+            };
+            attributes.push(format!(" {}=\"{}\"", attr, escape_xml(&value)));
+        }
+        if name == "img" && element.attr("src").is_none() {
+            return;
+        }
+
+        self.output.push('<');
+        self.output.push_str(name);
+        self.output.push_str(&attributes.concat());
+        if VOID_TAGS.contains(&name) {
+            self.output.push_str("/>");
+            return;
+        }
+        self.output.push('>');
+        self.write_children(node);
+        self.output.push_str(&format!("</{}>", name));
+    }
+
+    /// Path of the image inside the book, when its file is in the resource
+    /// directory and of a supported type
+    fn embed_image(&mut self, src: &str) -> Option<String> {
+        let dir = self.resource_dir?;
+        let file_name = src.split(['?', '#']).next()?.rsplit('/').next()?;
+        let path = dir.join(file_name);
+        if file_name.is_empty() || !path.is_file() {
+            return None;
+        }
+
+        if let Some(image) = self.images.iter().find(|image| image.path == path) {
+            return Some(image.href.clone());
+        }
+
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        let media_type = IMAGE_MEDIA_TYPES.iter().find(|(ext, _)| *ext == extension)?.1;
+        let id = format!("image-{}", self.images.len() + 1);
+        let href = format!("images/{}.{}", id, extension);
+        self.images.push(EpubImage { id, href: href.clone(), media_type, path });
+        Some(href)
+    }
+}
+
+/// `src` of a video or audio element, or of its first `<source>`
+fn media_source<'a>(media: &ElementRef<'a>) -> Option<&'a str> {
+    media.value().attr("src")
+        .or_else(|| media.children()
+            .filter_map(ElementRef::wrap)
+            .find(|child| child.value().name() == "source")
+            .and_then(|source| source.value().attr("src")))
+        .map(str::trim)
+        .filter(|src| !src.is_empty())
+}
+
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Escape text for XML, dropping characters XML 1.0 does not allow
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() || c == '\u{FFFE}' || c == '\u{FFFF}' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Read};
+    use zip::ZipArchive;
+
+    fn read_entry(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> String {
+        let mut content = Vec::new();
+        archive.by_name(name).unwrap().read_to_end(&mut content).unwrap();
+        String::from_utf8_lossy(&content).into_owned()
+    }
+
+    #[test]
+    fn test_write_epub() {
+        let dir = std::env::temp_dir().join(format!("readability-epub-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("photo.png"), b"\x89PNG").unwrap();
+
+        let articles = vec![
+            Article {
+                title: Some("Fish & Chips".to_string()),
+                byline: Some("Jane Doe".to_string()),
+                content: Some(r#"<p onclick="x()">One<br>Two &amp; three</p>
+                    <img src="https://example.com/img/photo.png?w=200" srcset="a.png 2x">
+                    <img src="https://example.com/missing.png"><script>bad()</script>"#.to_string()),
+                ..Default::default()
+            },
+            Article {
+                title: Some("Second".to_string()),
+                lang: Some("fr".to_string()),
+                content: Some("<p>Deux</p>".to_string()),
+                ..Default::default()
+            },
+        ];
+        let options = EpubOptions { resource_dir: Some(dir.clone()), ..Default::default() };
+
+        let mut buffer = Cursor::new(Vec::new());
+        write_epub(&articles, &options, &mut buffer).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let mut archive = ZipArchive::new(Cursor::new(buffer.into_inner())).unwrap();
+        {
+            let mimetype = archive.by_index(0).unwrap();
+            assert_eq!(mimetype.name(), "mimetype");
+            assert_eq!(mimetype.compression(), CompressionMethod::Stored);
+        }
+
+        let chapter = read_entry(&mut archive, "OEBPS/chapter-1.xhtml");
+        assert!(chapter.contains("<title>Fish &amp; Chips</title>"));
+        assert!(chapter.contains(r#"<p class="byline">By Jane Doe</p>"#));
+        assert!(chapter.contains("<p>One<br/>Two &amp; three</p>"));
+        assert!(chapter.contains(r#"<img src="images/image-1.png"/>"#));
+        assert!(!chapter.contains("missing.png"));
+        assert!(!chapter.contains("script"));
+        assert!(read_entry(&mut archive, "OEBPS/chapter-2.xhtml").contains(r#"xml:lang="fr""#));
+
+        let package = read_entry(&mut archive, "OEBPS/content.opf");
+        assert!(package.contains("<dc:title>Articles</dc:title>"));
+        assert!(package.contains("<dc:creator>Jane Doe</dc:creator>"));
+        assert!(package.contains("<dc:language>en</dc:language>"));
+        assert!(package.contains(r#"<item id="image-1" href="images/image-1.png" media-type="image/png"/>"#));
+
+        let nav = read_entry(&mut archive, "OEBPS/nav.xhtml");
+        assert!(nav.contains(r#"<li><a href="chapter-2.xhtml">Second</a></li>"#));
+        assert_eq!(archive.by_name("OEBPS/images/image-1.png").unwrap().size(), 4);
+    }
+
+    #[test]
+    fn test_media_become_links() {
+        let article = Article {
+            content: Some(r#"<video controls><source src="https://example.com/v.mp4"><track src="subs.vtt"></video>
+                <audio src="https://example.com/a.mp3"></audio><video></video>"#.to_string()),
+            ..Default::default()
+        };
+        let chapter = chapter_document(&article, 0, "en", None, &mut Vec::new());
+        assert!(chapter.contains(r#"<a href="https://example.com/v.mp4">Video</a>"#));
+        assert!(chapter.contains(r#"<a href="https://example.com/a.mp3">Audio</a>"#));
+        assert!(!chapter.contains("<video") && !chapter.contains("<audio") && !chapter.contains("subs.vtt"));
+    }
+
+    #[test]
+    fn test_write_epub_without_articles() {
+        let result = write_epub(&[], &EpubOptions::default(), Cursor::new(Vec::new()));
+        assert!(matches!(result, Err(EpubError::NoArticles)));
+    }
+}
@@ -0,0 +1,266 @@
+//! Exporters rendering an article into the output formats
+
+use thiserror::Error;
+use crate::{asciidoc, latex, org, Article};
+use crate::utils::normalize_whitespace;
+
+/// Errors that can occur while exporting an article
+#[derive(Error, Debug)]
+pub enum ExportError {
+    #[error("No citation metadata found in the document")]
+    NoCitation,
+    #[error("JSON serialization failed: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Renders an article, from its cleaned content and metadata, into an
+/// output format
+pub trait Exporter {
+    fn export(&self, article: &Article) -> Result<String, ExportError>;
+}
+
+/// The whole `Article` as JSON
+pub struct JsonExporter;
+
+impl Exporter for JsonExporter {
+    fn export(&self, article: &Article) -> Result<String, ExportError> {
+        Ok(serde_json::to_string_pretty(article)?)
+    }
+}
+
+/// Plain text, see `Article::to_text`
+#[derive(Default)]
+pub struct TextExporter {
+    /// Hard-wrap paragraphs at this many characters
+    pub wrap_width: Option<usize>,
+}
+
+impl Exporter for TextExporter {
+    fn export(&self, article: &Article) -> Result<String, ExportError> {
+        Ok(article.to_text(self.wrap_width))
+    }
+}
+
+/// A standalone HTML page holding the content under its title and byline
+pub struct HtmlExporter;
+
+impl Exporter for HtmlExporter {
+    fn export(&self, article: &Article) -> Result<String, ExportError> {
+        let mut output = String::new();
+        output.push_str("<!DOCTYPE html>\n<html>\n<head>\n");
+
+        if let Some(title) = &article.title {
+            output.push_str(&format!("    <title>{}</title>\n", html_escape(title)));
+        }
+
+        output.push_str("    <meta charset=\"utf-8\">\n");
+        output.push_str("    <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+        output.push_str("</head>\n<body>\n");
+
+        if let Some(title) = &article.title {
+            output.push_str(&format!("    <h1>{}</h1>\n", html_escape(title)));
+        }
+
+        if let Some(byline) = &article.byline {
+            output.push_str(&format!("    <p class=\"byline\">By {}</p>\n", html_escape(byline)));
+        }
+
+        if let Some(content) = &article.content {
+            output.push_str("    <div class=\"content\">\n");
+            output.push_str(content);
+            output.push_str("\n    </div>\n");
+        }
+
+        output.push_str("</body>\n</html>\n");
+        Ok(output)
+    }
+}
+
+/// GitHub Flavored Markdown, see `Article::to_markdown`
+pub struct MarkdownExporter;
+
+impl Exporter for MarkdownExporter {
+    fn export(&self, article: &Article) -> Result<String, ExportError> {
+        Ok(article.to_markdown())
+    }
+}
+
+/// The block tree of the content as JSON, see `Article::blocks`
+pub struct BlocksExporter;
+
+impl Exporter for BlocksExporter {
+    fn export(&self, article: &Article) -> Result<String, ExportError> {
+        Ok(serde_json::to_string_pretty(&article.blocks())?)
+    }
+}
+
+/// JSON in the shape of Readability.js, see `Article::to_mozilla`
+pub struct MozillaJsonExporter;
+
+impl Exporter for MozillaJsonExporter {
+    fn export(&self, article: &Article) -> Result<String, ExportError> {
+        Ok(serde_json::to_string_pretty(&article.to_mozilla())?)
+    }
+}
+
+/// A BibTeX entry of the citation metadata
+pub struct BibtexExporter;
+
+impl Exporter for BibtexExporter {
+    fn export(&self, article: &Article) -> Result<String, ExportError> {
+        let citation = article.citation.as_ref().ok_or(ExportError::NoCitation)?;
+        Ok(citation.to_bibtex())
+    }
+}
+
+/// A CSL-JSON item list of the citation metadata
+pub struct CslJsonExporter;
+
+impl Exporter for CslJsonExporter {
+    fn export(&self, article: &Article) -> Result<String, ExportError> {
+        let citation = article.citation.as_ref().ok_or(ExportError::NoCitation)?;
+        // CSL-JSON files hold a list of items
+        Ok(serde_json::to_string_pretty(&vec![citation.to_csl_json()])?)
+    }
+}
+
+/// A standalone LaTeX document
+pub struct LatexExporter;
+
+impl Exporter for LatexExporter {
+    fn export(&self, article: &Article) -> Result<String, ExportError> {
+        let field = |value: &Option<String>| value.as_deref().map(latex::escape_text).unwrap_or_default();
+
+        let mut output = String::new();
+        output.push_str("\\documentclass{article}\n");
+        output.push_str("\\usepackage[utf8]{inputenc}\n");
+        output.push_str("\\usepackage[T1]{fontenc}\n");
+        output.push_str("\\usepackage[normalem]{ulem}\n");
+        output.push_str("\\usepackage{hyperref}\n\n");
+        output.push_str(&format!("\\title{{{}}}\n", field(&article.title)));
+        output.push_str(&format!("\\author{{{}}}\n", field(&article.byline)));
+        output.push_str(&format!("\\date{{{}}}\n\n", field(&article.published_time)));
+        output.push_str("\\begin{document}\n\n\\maketitle\n\n");
+        output.push_str(&latex::blocks_to_latex(&article.blocks()));
+        output.push_str("\n\\end{document}\n");
+        Ok(output)
+    }
+}
+
+/// An AsciiDoc document headed by the title and byline
+pub struct AsciiDocExporter;
+
+impl Exporter for AsciiDocExporter {
+    fn export(&self, article: &Article) -> Result<String, ExportError> {
+        let mut output = String::new();
+        if let Some(title) = article.title.as_deref().filter(|title| !title.is_empty()) {
+            output.push_str(&format!("= {}\n", asciidoc::escape_text(title)));
+            if let Some(byline) = article.byline.as_deref().filter(|byline| !byline.is_empty()) {
+                output.push_str(&format!("{}\n", byline));
+            }
+            if let Some(lang) = &article.lang {
+                output.push_str(&format!(":lang: {}\n", lang));
+            }
+            output.push('\n');
+        }
+        output.push_str(&asciidoc::blocks_to_asciidoc(&article.blocks()));
+        Ok(output)
+    }
+}
+
+/// An Org-mode document with the metadata as export keywords
+pub struct OrgExporter;
+
+impl Exporter for OrgExporter {
+    fn export(&self, article: &Article) -> Result<String, ExportError> {
+        let keywords = [
+            ("TITLE", &article.title),
+            ("AUTHOR", &article.byline),
+            ("DATE", &article.published_time),
+            ("LANGUAGE", &article.lang),
+        ];
+
+        let mut output = String::new();
+        for (keyword, value) in keywords {
+            // Keywords end at the line end
+            let value = value.as_deref().map(normalize_whitespace).unwrap_or_default();
+            if !value.is_empty() {
+                output.push_str(&format!("#+{}: {}\n", keyword, value));
+            }
+        }
+        if !output.is_empty() {
+            output.push('\n');
+        }
+        output.push_str(&org::blocks_to_org(&article.blocks()));
+        Ok(output)
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article() -> Article {
+        Article {
+            title: Some("Fish & Chips".to_string()),
+            byline: Some("Jane Doe".to_string()),
+            content: Some("<p>Some <em>fine</em> food.</p>".to_string()),
+            published_time: Some("2024-01-02".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_html_escape() {
+        assert_eq!(html_escape("Hello & <World>"), "Hello &amp; &lt;World&gt;");
+        assert_eq!(html_escape("\"Test\""), "&quot;Test&quot;");
+    }
+
+    #[test]
+    fn test_latex_exporter() {
+        let latex = LatexExporter.export(&article()).unwrap();
+        assert!(latex.starts_with("\\documentclass{article}\n"));
+        assert!(latex.contains("\\title{Fish \\& Chips}\n\\author{Jane Doe}\n\\date{2024-01-02}\n"));
+        assert!(latex.ends_with("\\maketitle\n\nSome \\emph{fine} food.\n\n\\end{document}\n"));
+    }
+
+    #[test]
+    fn test_asciidoc_exporter() {
+        assert_eq!(
+            AsciiDocExporter.export(&article()).unwrap(),
+            "= Fish & Chips\nJane Doe\n\nSome __fine__ food.\n"
+        );
+    }
+
+    #[test]
+    fn test_org_exporter() {
+        assert_eq!(
+            OrgExporter.export(&article()).unwrap(),
+            "#+TITLE: Fish & Chips\n#+AUTHOR: Jane Doe\n#+DATE: 2024-01-02\n\nSome /fine/ food.\n"
+        );
+    }
+
+    #[test]
+    fn test_org_exporter_keywords_stay_on_one_line() {
+        let article = Article {
+            title: Some("Fish\n#+INCLUDE: x".to_string()),
+            byline: Some(" Jane\nDoe ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(OrgExporter.export(&article).unwrap(), "#+TITLE: Fish #+INCLUDE: x\n#+AUTHOR: Jane Doe\n\n");
+    }
+
+    #[test]
+    fn test_citation_exporters_need_citation() {
+        assert!(matches!(BibtexExporter.export(&article()), Err(ExportError::NoCitation)));
+        assert!(matches!(CslJsonExporter.export(&article()), Err(ExportError::NoCitation)));
+    }
+}
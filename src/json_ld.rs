@@ -0,0 +1,125 @@
+//! JSON-LD metadata extraction for the Readability parser
+
+use scraper::{Html, Selector};
+use serde_json::Value;
+use crate::regexps::get_regexps;
+
+/// Find the schema.org article object described by the document's
+/// `<script type="application/ld+json">` blocks, like Readability.js `_getJSONLD`
+pub fn find_article_object(document: &Html) -> Option<Value> {
+    let script_selector = Selector::parse(r#"script[type="application/ld+json"]"#).unwrap();
+
+    for script in document.select(&script_selector) {
+        // Strip CDATA markers if present
+        let content = script.text().collect::<String>();
+        let content = content.trim();
+        let content = content
+            .strip_prefix("<![CDATA[")
+            .and_then(|c| c.strip_suffix("]]>"))
+            .unwrap_or(content);
+
+        let parsed: Value = match serde_json::from_str(content) {
+            Ok(parsed) => parsed,
+            Err(_) => continue,
+        };
+
+        let parsed = match parsed {
+            Value::Array(items) => match items.into_iter().find(is_article_object) {
+                Some(item) => item,
+                None => continue,
+            },
+            other => other,
+        };
+
+        if !has_schema_org_context(&parsed) {
+            continue;
+        }
+
+        if parsed.get("@type").is_none() {
+            if let Some(Value::Array(graph)) = parsed.get("@graph") {
+                if let Some(item) = graph.iter().find(|item| is_article_object(item)) {
+                    return Some(item.clone());
+                }
+            }
+        }
+
+        if is_article_object(&parsed) {
+            return Some(parsed);
+        }
+    }
+
+    None
+}
+
+/// Check whether a JSON-LD object has one of the schema.org article types
+pub fn is_article_object(value: &Value) -> bool {
+    let article_types = &get_regexps().json_ld_article_types;
+    match value.get("@type") {
+        Some(Value::String(kind)) => article_types.is_match(kind),
+        Some(Value::Array(kinds)) => kinds.iter()
+            .filter_map(Value::as_str)
+            .any(|kind| article_types.is_match(kind)),
+        _ => false,
+    }
+}
+
+/// Get a string property of a JSON-LD object, trimmed and non-empty
+pub fn get_string(value: &Value, key: &str) -> Option<String> {
+    value.get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+fn has_schema_org_context(value: &Value) -> bool {
+    let context = match value.get("@context") {
+        Some(Value::String(context)) => context.as_str(),
+        Some(Value::Object(context)) => match context.get("@vocab").and_then(Value::as_str) {
+            Some(vocab) => vocab,
+            None => return false,
+        },
+        _ => return false,
+    };
+
+    let context = context.trim_end_matches('/');
+    context == "http://schema.org" || context == "https://schema.org"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_article_object() {
+        let html = r#"<html><head><script type="application/ld+json">
+            {"@context": "https://schema.org", "@type": "NewsArticle", "headline": "Hello"}
+        </script></head></html>"#;
+        let document = Html::parse_document(html);
+        let article = find_article_object(&document).unwrap();
+        assert_eq!(get_string(&article, "headline"), Some("Hello".to_string()));
+    }
+
+    #[test]
+    fn test_find_article_object_in_graph() {
+        let html = r#"<html><head><script type="application/ld+json">
+            {"@context": "http://schema.org/", "@graph": [
+                {"@type": "WebSite", "name": "Site"},
+                {"@type": ["BlogPosting"], "headline": "Post"}
+            ]}
+        </script></head></html>"#;
+        let document = Html::parse_document(html);
+        let article = find_article_object(&document).unwrap();
+        assert_eq!(get_string(&article, "headline"), Some("Post".to_string()));
+    }
+
+    #[test]
+    fn test_ignores_other_contexts_and_invalid_json() {
+        let html = r#"<html><head>
+            <script type="application/ld+json">{ not json </script>
+            <script type="application/ld+json">{"@context": "https://example.com", "@type": "Article"}</script>
+        </head></html>"#;
+        let document = Html::parse_document(html);
+        assert!(find_article_object(&document).is_none());
+    }
+}
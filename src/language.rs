@@ -0,0 +1,258 @@
+//! Offline language detection for the Readability parser
+//!
+//! Text in a script used by a single language (Greek, Hebrew, Thai, Hangul...)
+//! is identified by script alone. Latin and Cyrillic text is compared against
+//! small character trigram profiles built from sample sentences.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Only this many characters of the article are looked at
+const MAX_SAMPLE_CHARS: usize = 10_000;
+
+/// Below this many letters there is not enough text to tell
+const MIN_LETTERS: usize = 20;
+
+/// Language detected from the text of an article
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectedLanguage {
+    /// ISO 639-1 language code
+    pub code: String,
+    /// Confidence of the detection, between 0 and 1
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Armenian,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Bengali,
+    Tamil,
+    Thai,
+    Georgian,
+    Hangul,
+    Kana,
+    Han,
+    Other,
+}
+
+impl Script {
+    fn of(c: char) -> Script {
+        match c as u32 {
+            0x0041..=0x024F | 0x1E00..=0x1EFF => Script::Latin,
+            0x0370..=0x03FF | 0x1F00..=0x1FFF => Script::Greek,
+            0x0400..=0x052F => Script::Cyrillic,
+            0x0530..=0x058F => Script::Armenian,
+            0x0590..=0x05FF | 0xFB1D..=0xFB4F => Script::Hebrew,
+            0x0600..=0x06FF | 0x0750..=0x077F | 0x08A0..=0x08FF | 0xFB50..=0xFDFF | 0xFE70..=0xFEFF => Script::Arabic,
+            0x0900..=0x097F => Script::Devanagari,
+            0x0980..=0x09FF => Script::Bengali,
+            0x0B80..=0x0BFF => Script::Tamil,
+            0x0E00..=0x0E7F => Script::Thai,
+            0x10A0..=0x10FF => Script::Georgian,
+            0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Script::Hangul,
+            0x3040..=0x30FF | 0x31F0..=0x31FF => Script::Kana,
+            0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF => Script::Han,
+            _ => Script::Other,
+        }
+    }
+
+    /// The language written in this script when it is used by essentially one
+    fn single_language(self) -> Option<&'static str> {
+        match self {
+            Script::Greek => Some("el"),
+            Script::Armenian => Some("hy"),
+            Script::Hebrew => Some("he"),
+            Script::Arabic => Some("ar"),
+            Script::Devanagari => Some("hi"),
+            Script::Bengali => Some("bn"),
+            Script::Tamil => Some("ta"),
+            Script::Thai => Some("th"),
+            Script::Georgian => Some("ka"),
+            Script::Hangul => Some("ko"),
+            Script::Kana => Some("ja"),
+            Script::Han => Some("zh"),
+            _ => None,
+        }
+    }
+}
+
+/// Sample text the trigram profiles are built from
+const PROFILE_SAMPLES: &[(&str, Script, &str)] = &[
+    ("en", Script::Latin, "The government said on Monday that it would not change the plan, and that the people who have been waiting for an answer should know that this is what they were told. It was one of the most important things that we have seen in the last year, with more than half of them saying they would like to be there when it happens."),
+    ("de", Script::Latin, "Die Regierung hat am Montag gesagt, dass sie den Plan nicht ändern wird, und dass die Menschen, die auf eine Antwort warten, wissen sollten, was ihnen gesagt wurde. Es war eines der wichtigsten Dinge, die wir im letzten Jahr gesehen haben, und mehr als die Hälfte von ihnen sagt, dass sie dabei sein möchten, wenn es geschieht."),
+    ("fr", Script::Latin, "Le gouvernement a déclaré lundi qu'il ne changerait pas le plan, et que les personnes qui attendent une réponse doivent savoir que c'est ce qui leur a été dit. C'était l'une des choses les plus importantes que nous avons vues au cours de la dernière année, et plus de la moitié d'entre eux disent qu'ils aimeraient être là quand cela arrivera."),
+    ("es", Script::Latin, "El gobierno dijo el lunes que no cambiaría el plan, y que las personas que esperan una respuesta deben saber que esto es lo que se les dijo. Fue una de las cosas más importantes que hemos visto en el último año, y más de la mitad de ellos dicen que les gustaría estar allí cuando ocurra."),
+    ("pt", Script::Latin, "O governo disse na segunda-feira que não mudaria o plano, e que as pessoas que estão à espera de uma resposta devem saber que foi isso que lhes foi dito. Foi uma das coisas mais importantes que vimos no último ano, e mais da metade deles diz que gostaria de estar lá quando isso acontecer."),
+    ("it", Script::Latin, "Il governo ha detto lunedì che non cambierà il piano, e che le persone che aspettano una risposta dovrebbero sapere che questo è ciò che è stato detto loro. È stata una delle cose più importanti che abbiamo visto nell'ultimo anno, e più della metà di loro dice che vorrebbe essere lì quando succederà."),
+    ("nl", Script::Latin, "De regering zei maandag dat ze het plan niet zou veranderen, en dat de mensen die op een antwoord wachten moeten weten dat dit is wat hun werd verteld. Het was een van de belangrijkste dingen die we het afgelopen jaar hebben gezien, en meer dan de helft van hen zegt dat ze erbij willen zijn wanneer het gebeurt."),
+    ("sv", Script::Latin, "Regeringen sade på måndagen att den inte skulle ändra planen, och att de människor som väntar på ett svar borde veta att det är detta som de har fått höra. Det var en av de viktigaste sakerna som vi har sett under det senaste året, och mer än hälften av dem säger att de vill vara där när det händer."),
+    ("pl", Script::Latin, "Rząd powiedział w poniedziałek, że nie zmieni planu, a ludzie, którzy czekają na odpowiedź, powinni wiedzieć, że właśnie to im powiedziano. Była to jedna z najważniejszych rzeczy, jakie widzieliśmy w ostatnim roku, a ponad połowa z nich mówi, że chciałaby tam być, kiedy to się stanie."),
+    ("tr", Script::Latin, "Hükümet pazartesi günü planı değiştirmeyeceğini ve bir cevap bekleyen insanların kendilerine söylenenin bu olduğunu bilmesi gerektiğini söyledi. Bu, geçen yıl gördüğümüz en önemli şeylerden biriydi ve yarısından fazlası bu olduğunda orada olmak istediklerini söylüyor."),
+    ("ru", Script::Cyrillic, "Правительство заявило в понедельник, что не будет менять план, и что люди, которые ждут ответа, должны знать, что именно это им было сказано. Это было одно из самых важных событий, которые мы видели за последний год, и более половины из них говорят, что хотели бы быть там, когда это произойдёт."),
+    ("uk", Script::Cyrillic, "Уряд заявив у понеділок, що не змінюватиме план, і що люди, які чекають на відповідь, мають знати, що саме це їм було сказано. Це була одна з найважливіших подій, які ми бачили за останній рік, і більше половини з них кажуть, що хотіли б бути там, коли це станеться."),
+];
+
+struct Profile {
+    code: &'static str,
+    script: Script,
+    trigrams: HashMap<String, f64>,
+    norm: f64,
+}
+
+fn profiles() -> &'static [Profile] {
+    static PROFILES: OnceLock<Vec<Profile>> = OnceLock::new();
+    PROFILES.get_or_init(|| {
+        PROFILE_SAMPLES.iter()
+            .map(|(code, script, sample)| {
+                let trigrams = trigram_frequencies(sample);
+                let norm = vector_norm(&trigrams);
+                Profile { code, script: *script, trigrams, norm }
+            })
+            .collect()
+    })
+}
+
+/// Detect the language of a text, or `None` when there is too little of it
+pub fn detect_language(text: &str) -> Option<DetectedLanguage> {
+    let sample: String = text.chars().take(MAX_SAMPLE_CHARS).collect();
+
+    let mut script_counts: HashMap<Script, usize> = HashMap::new();
+    for c in sample.chars().filter(|c| c.is_alphabetic()) {
+        *script_counts.entry(Script::of(c)).or_insert(0) += 1;
+    }
+    let letters: usize = script_counts.values().sum();
+    if letters < MIN_LETTERS {
+        return None;
+    }
+
+    // Japanese mixes kana with Han characters
+    let kana = script_counts.get(&Script::Kana).copied().unwrap_or(0);
+    if kana > 0 {
+        let han = script_counts.remove(&Script::Han).unwrap_or(0);
+        *script_counts.entry(Script::Kana).or_insert(0) += han;
+    }
+
+    let (script, count) = script_counts.into_iter().max_by_key(|(_, count)| *count)?;
+    let script_share = count as f64 / letters as f64;
+
+    if let Some(code) = script.single_language() {
+        return Some(DetectedLanguage { code: code.to_string(), confidence: round(script_share) });
+    }
+
+    let trigrams = trigram_frequencies(&sample);
+    let norm = vector_norm(&trigrams);
+    if norm == 0.0 {
+        return None;
+    }
+
+    let mut scores: Vec<(&str, f64)> = profiles().iter()
+        .filter(|profile| profile.script == script)
+        .map(|profile| {
+            let dot: f64 = trigrams.iter()
+                .filter_map(|(trigram, count)| profile.trigrams.get(trigram).map(|p| p * count))
+                .sum();
+            (profile.code, dot / (norm * profile.norm))
+        })
+        .collect();
+    scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    let (code, best) = *scores.first()?;
+    if best <= 0.0 {
+        return None;
+    }
+    let second = scores.get(1).map(|(_, score)| *score).unwrap_or(0.0);
+
+    // How far ahead of the runner-up the best profile is
+    let margin = ((best - second) / best * 2.0).min(1.0);
+    Some(DetectedLanguage { code: code.to_string(), confidence: round(script_share * margin) })
+}
+
+/// Normalize a declared language such as `en_US` or `EN-us` to a BCP 47 tag
+pub fn normalize_language_tag(tag: &str) -> Option<String> {
+    let mut parts = tag.trim().split(['-', '_']).filter(|part| !part.is_empty());
+    let primary = parts.next()?.to_lowercase();
+    if !primary.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let mut normalized = primary;
+    for part in parts {
+        normalized.push('-');
+        if part.len() == 2 {
+            normalized.push_str(&part.to_uppercase());
+        } else {
+            normalized.push_str(part);
+        }
+    }
+    Some(normalized)
+}
+
+fn trigram_frequencies(text: &str) -> HashMap<String, f64> {
+    let mut trigrams = HashMap::new();
+    for word in text.split(|c: char| !c.is_alphabetic()).filter(|word| !word.is_empty()) {
+        let padded: Vec<char> = std::iter::once(' ')
+            .chain(word.chars().flat_map(char::to_lowercase))
+            .chain(std::iter::once(' '))
+            .collect();
+        for window in padded.windows(3) {
+            *trigrams.entry(window.iter().collect()).or_insert(0.0) += 1.0;
+        }
+    }
+    trigrams
+}
+
+fn vector_norm(vector: &HashMap<String, f64>) -> f64 {
+    vector.values().map(|v| v * v).sum::<f64>().sqrt()
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detected_code(text: &str) -> Option<String> {
+        detect_language(text).map(|detected| detected.code)
+    }
+
+    #[test]
+    fn test_detect_latin_languages() {
+        assert_eq!(detected_code("This is an article about the weather in the city, and what people think of it after the long winter we have had."), Some("en".to_string()));
+        assert_eq!(detected_code("Dies ist ein Artikel über das Wetter in der Stadt und darüber, was die Leute nach dem langen Winter davon halten."), Some("de".to_string()));
+        assert_eq!(detected_code("Ceci est un article sur la météo dans la ville, et sur ce que les gens en pensent après le long hiver que nous avons eu."), Some("fr".to_string()));
+        assert_eq!(detected_code("Este es un artículo sobre el tiempo en la ciudad y lo que la gente piensa de él después del largo invierno que hemos tenido."), Some("es".to_string()));
+    }
+
+    #[test]
+    fn test_detect_by_script() {
+        let detected = detect_language("Αυτό είναι ένα άρθρο για τον καιρό στην πόλη").unwrap();
+        assert_eq!(detected.code, "el");
+        assert_eq!(detected.confidence, 1.0);
+        assert_eq!(detected_code("これは東京の天気についての記事です。長い冬の後で人々がどう思っているか"), Some("ja".to_string()));
+        assert_eq!(detected_code("这是一篇关于城市天气的文章，以及人们在漫长的冬天之后对它的看法"), Some("zh".to_string()));
+        assert_eq!(detected_code("Это статья о погоде в городе и о том, что люди думают о ней после долгой зимы."), Some("ru".to_string()));
+    }
+
+    #[test]
+    fn test_detect_too_short() {
+        assert_eq!(detect_language("Hello"), None);
+        assert_eq!(detect_language("1234 5678 !!!"), None);
+    }
+
+    #[test]
+    fn test_normalize_language_tag() {
+        assert_eq!(normalize_language_tag("en_US"), Some("en-US".to_string()));
+        assert_eq!(normalize_language_tag(" DE "), Some("de".to_string()));
+        assert_eq!(normalize_language_tag("zh-Hant-tw"), Some("zh-Hant-TW".to_string()));
+        assert_eq!(normalize_language_tag(""), None);
+    }
+}
@@ -0,0 +1,237 @@
+//! LaTeX rendering of the article block tree
+
+use crate::blocks::{render_spans, single_line, Block, EmbedKind, Mark, Span, TableRow};
+
+/// Counters of nested `enumerate` environments, by depth
+const ENUM_COUNTERS: &[&str] = &["enumi", "enumii", "enumiii", "enumiv"];
+
+/// Render blocks as the body of a LaTeX document
+pub fn blocks_to_latex(blocks: &[Block]) -> String {
+    let mut latex = render_blocks(blocks, 0).join("\n\n");
+    if !latex.is_empty() {
+        latex.push('\n');
+    }
+    latex
+}
+
+/// `enum_depth` is the number of enclosing numbered lists, which decides
+/// the counter a list start is set on
+fn render_blocks(blocks: &[Block], enum_depth: usize) -> Vec<String> {
+    blocks.iter()
+        .map(|block| render_block(block, enum_depth))
+        .filter(|block| !block.is_empty())
+        .collect()
+}
+
+fn render_block(block: &Block, enum_depth: usize) -> String {
+    match block {
+        Block::Heading { level, spans } => {
+            let command = match level {
+                1 => "section",
+                2 => "subsection",
+                3 => "subsubsection",
+                4 => "paragraph",
+                _ => "subparagraph",
+            };
+            format!("\\{}*{{{}}}", command, render_inline(&single_line(spans)))
+        }
+        Block::Paragraph { spans } => render_inline(spans),
+        Block::List { ordered, start, items } => {
+            let environment = if *ordered { "enumerate" } else { "itemize" };
+            let mut lines = vec![format!("\\begin{{{}}}", environment)];
+            if let (true, Some(start), Some(counter)) = (*ordered, start, ENUM_COUNTERS.get(enum_depth)) {
+                if *start != 1 {
+                    lines.push(format!("\\setcounter{{{}}}{{{}}}", counter, start.saturating_sub(1)));
+                }
+            }
+            let item_depth = if *ordered { enum_depth + 1 } else { enum_depth };
+            for item in items {
+                let content = render_blocks(item, item_depth).join("\n\n");
+                // The empty group keeps content starting with "[" from
+                // being read as the optional item label
+                lines.push(if content.is_empty() { "\\item".to_string() } else { format!("\\item{{}} {}", content) });
+            }
+            lines.push(format!("\\end{{{}}}", environment));
+            lines.join("\n")
+        }
+        Block::Quote { blocks } => {
+            format!("\\begin{{quote}}\n{}\n\\end{{quote}}", render_blocks(blocks, enum_depth).join("\n\n"))
+        }
+        Block::Code { text, .. } => render_code(text),
+        Block::Image { src, alt, caption, link } => {
+            // Remote images cannot be included, so they are linked
+            let label = alt.as_deref().filter(|alt| !alt.is_empty()).unwrap_or("Image");
+            let mut lines = vec![
+                "\\begin{center}".to_string(),
+                format!("\\href{{{}}}{{{}}}", escape_url(src), escape_text(label)),
+            ];
+            if let Some(link) = link {
+                lines.push(format!("\\\\\n\\url{{{}}}", escape_url(link)));
+            }
+            if let Some(caption) = caption {
+                lines.push(format!("\\\\\n\\emph{{{}}}", escape_text(caption)));
+            }
+            lines.push("\\end{center}".to_string());
+            lines.join("\n")
+        }
+        Block::Table { rows } => render_table(rows),
+        Block::Embed { kind, src } => {
+            let label = match kind {
+                EmbedKind::Video => "Video",
+                EmbedKind::Audio => "Audio",
+                EmbedKind::Iframe | EmbedKind::Object => "Embedded content",
+            };
+            format!("{}: \\url{{{}}}", label, escape_url(src))
+        }
+        Block::Rule => "\\noindent\\rule{\\linewidth}{0.4pt}".to_string(),
+    }
+}
+
+/// Lines that would end the `verbatim` environment early are set outside of
+/// it in typewriter type
+fn render_code(text: &str) -> String {
+    let verbatim = |lines: &[&str]| format!("\\begin{{verbatim}}\n{}\n\\end{{verbatim}}", lines.join("\n"));
+    let mut parts = Vec::new();
+    let mut lines = Vec::new();
+    for line in text.split('\n') {
+        if line.contains("\\end{verbatim}") {
+            if !lines.is_empty() {
+                parts.push(verbatim(&lines));
+                lines.clear();
+            }
+            parts.push(format!("\\noindent\\texttt{{{}}}\\par", escape_text(line).replace(' ', "~")));
+        } else {
+            lines.push(line);
+        }
+    }
+    if !lines.is_empty() {
+        parts.push(verbatim(&lines));
+    }
+    parts.join("\n")
+}
+
+fn render_table(rows: &[TableRow]) -> String {
+    let columns = rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+
+    let mut lines = vec![format!("\\begin{{tabular}}{{{}}}", "l".repeat(columns)), "\\hline".to_string()];
+    for (i, row) in rows.iter().enumerate() {
+        let mut cells: Vec<String> = row.cells.iter().map(|cell| render_inline(&single_line(cell))).collect();
+        cells.resize(columns, String::new());
+        lines.push(format!("{} \\\\", cells.join(" & ")));
+        // Rule under the header rows
+        if row.header && rows.get(i + 1).is_some_and(|next| !next.header) {
+            lines.push("\\hline".to_string());
+        }
+    }
+    lines.push("\\hline".to_string());
+    lines.push("\\end{tabular}".to_string());
+    lines.join("\n")
+}
+
+fn render_inline(spans: &[Span]) -> String {
+    render_spans(
+        spans,
+        escape_text,
+        |mark, text| {
+            let command = match mark {
+                Mark::Bold => "textbf",
+                Mark::Italic => "emph",
+                Mark::Underline => "uline",
+                Mark::Strikethrough => "sout",
+                Mark::Code => "texttt",
+                Mark::Superscript => "textsuperscript",
+                Mark::Subscript => "textsubscript",
+            };
+            format!("\\{}{{{}}}", command, text)
+        },
+        |target, text| format!("\\href{{{}}}{{{}}}", escape_url(target), text),
+    )
+}
+
+/// Escape LaTeX special characters, turning line breaks into `\\`
+pub(crate) fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '<' => escaped.push_str("\\textless{}"),
+            '>' => escaped.push_str("\\textgreater{}"),
+            '|' => escaped.push_str("\\textbar{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\\\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escape a URL for `\href` and `\url`
+fn escape_url(url: &str) -> String {
+    url.replace('\\', "%5C")
+        .replace('{', "%7B")
+        .replace('}', "%7D")
+        .replace(' ', "%20")
+        .replace('%', "\\%")
+        .replace('#', "\\#")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::html_to_blocks;
+
+    #[test]
+    fn test_latex_inline() {
+        let blocks = html_to_blocks(r#"<h2>Costs &amp; <em>benefits</em></h2>
+            <p>50% of <strong>$5 </strong>is <code>a_b</code>, see <a href="https://example.com/a#b">this</a><br>next</p>"#);
+        assert_eq!(
+            blocks_to_latex(&blocks),
+            "\\subsection*{Costs \\& \\emph{benefits}}\n\n\
+             50\\% of \\textbf{\\$5} is \\texttt{a\\_b}, see \\href{https://example.com/a\\#b}{this}\\\\\nnext\n"
+        );
+    }
+
+    #[test]
+    fn test_latex_blocks() {
+        let blocks = html_to_blocks(r#"<ol start="3"><li>Three<ul><li>Nested</li></ul></li></ol>
+            <blockquote><p>Quoted</p></blockquote><pre>x = {1}</pre>
+            <table><tr><th>A</th><th>B</th></tr><tr><td>1</td></tr></table><hr>"#);
+        assert_eq!(
+            blocks_to_latex(&blocks),
+            "\\begin{enumerate}\n\\setcounter{enumi}{2}\n\\item{} Three\n\n\\begin{itemize}\n\\item{} Nested\n\\end{itemize}\n\\end{enumerate}\n\n\
+             \\begin{quote}\nQuoted\n\\end{quote}\n\n\\begin{verbatim}\nx = {1}\n\\end{verbatim}\n\n\
+             \\begin{tabular}{ll}\n\\hline\nA & B \\\\\n\\hline\n1 &  \\\\\n\\hline\n\\end{tabular}\n\n\
+             \\noindent\\rule{\\linewidth}{0.4pt}\n"
+        );
+    }
+
+    #[test]
+    fn test_latex_linked_image() {
+        let blocks = html_to_blocks(r#"<a href="https://example.com/big.jpg"><img src="small.jpg" alt="Small"></a>"#);
+        assert_eq!(
+            blocks_to_latex(&blocks),
+            "\\begin{center}\n\\href{small.jpg}{Small}\n\\\\\n\\url{https://example.com/big.jpg}\n\\end{center}\n"
+        );
+    }
+
+    #[test]
+    fn test_latex_special_content() {
+        let blocks = html_to_blocks(r#"<ul><li>[draft] a &lt; b | c &gt; d</li></ul><pre>a  b
+\end{verbatim}
+c</pre>"#);
+        assert_eq!(
+            blocks_to_latex(&blocks),
+            "\\begin{itemize}\n\\item{} [draft] a \\textless{} b \\textbar{} c \\textgreater{} d\n\\end{itemize}\n\n\
+             \\begin{verbatim}\na  b\n\\end{verbatim}\n\\noindent\\texttt{\\textbackslash{}end\\{verbatim\\}}\\par\n\
+             \\begin{verbatim}\nc\n\\end{verbatim}\n"
+        );
+    }
+}
@@ -28,12 +28,34 @@
 //! }
 //! ```
 
+use chrono::{DateTime, FixedOffset};
 use regex::Regex;
 use scraper::{Html, Selector, ElementRef};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::BTreeMap;
 use thiserror::Error;
 
+mod asciidoc;
+mod authors;
+mod blocks;
+mod citation;
+mod epub;
+mod export;
+mod json_ld;
+mod language;
+mod latex;
+mod license;
+mod links;
+mod markdown;
+mod microdata;
+mod mozilla;
+mod org;
+mod paywall;
+mod readerable;
+mod sanitize;
+mod social;
+mod text;
 mod regexps;
 mod scoring;
 mod utils;
@@ -43,15 +65,73 @@ pub use regexps::{
     is_unlikely_candidate, has_positive_indicators, has_negative_indicators,
     is_byline, is_video_url, is_whitespace, has_content, contains_ad_words, contains_loading_words
 };
+pub use authors::Author;
+pub use blocks::{Block, Span, Mark, TableRow, EmbedKind};
+pub use citation::Citation;
+pub use epub::{EpubError, EpubOptions, write_epub};
+pub use export::{
+    Exporter, ExportError, JsonExporter, TextExporter, HtmlExporter, MarkdownExporter, BlocksExporter,
+    MozillaJsonExporter, BibtexExporter, CslJsonExporter, LatexExporter, AsciiDocExporter, OrgExporter,
+};
+pub use language::{DetectedLanguage, detect_language, normalize_language_tag};
+pub use license::License;
+pub use links::{ArticleLinks, Icon, AlternateLink};
+pub use mozilla::MozillaArticle;
+pub use paywall::{Paywall, PaywallSignal};
+pub use readerable::{
+    is_probably_readerable, assess_readerability, ReaderableOptions, ReaderableAssessment,
+    ReaderableNode, ReaderableFailure,
+};
+pub use sanitize::{SanitizeOptions, sanitize_html};
+pub use social::{OpenGraph, OpenGraphMedia, TwitterCard};
 pub use scoring::ContentScore;
 pub use utils::{
     to_absolute_uri, is_url, get_inner_text, get_char_count, is_phrasing_content,
     is_single_image, is_node_visible, has_ancestor_tag, get_node_ancestors,
     is_element_without_content, has_single_tag_inside_element, has_child_block_element,
     should_clean_attribute, extract_text_content, word_count, is_title_candidate,
-    unescape_html_entities, clean_text, get_link_density
+    unescape_html_entities, clean_text, get_link_density, parse_date, count_words,
+    reading_time_minutes,
+};
+use utils::{
+    normalize_whitespace, truncate_at_word_boundary, is_mostly_rtl, parse_srcset, parse_dimension,
+    text_similarity, UNLIKELY_ROLES,
 };
 
+/// Metadata keys that may hold the article title, in order of preference
+const METADATA_TITLE_KEYS: &[&str] = &[
+    "dc:title", "dcterm:title", "og:title", "weibo:article:title",
+    "weibo:webpage:title", "title", "twitter:title",
+];
+
+/// Metadata keys that may hold the publication time, in order of preference
+const PUBLISHED_TIME_KEYS: &[&str] = &[
+    "article:published_time", "parsely-pub-date", "og:published_time",
+    "dc:date", "dcterms:created", "date",
+];
+
+/// Metadata keys that may hold the last modification time, in order of preference
+const MODIFIED_TIME_KEYS: &[&str] = &[
+    "article:modified_time", "og:updated_time", "dcterms:modified",
+];
+
+/// Paragraphs shorter than this are not used as the excerpt if a longer one exists
+const EXCERPT_MIN_PARAGRAPH_LENGTH: usize = 25;
+
+/// Metadata keys that may hold the byline, in order of preference
+const BYLINE_KEYS: &[&str] = &[
+    "dc:creator", "dcterm:creator", "author", "parsely-author", "article:author",
+];
+
+/// Metadata keys that may hold the lead image, in order of preference
+const LEAD_IMAGE_KEYS: &[&str] = &[
+    "og:image:secure_url", "og:image:url", "og:image", "twitter:image", "twitter:image:src",
+];
+
+/// Content images narrower or shorter than this are not used as the lead image
+const LEAD_IMAGE_MIN_WIDTH: u32 = 300;
+const LEAD_IMAGE_MIN_HEIGHT: u32 = 200;
+
 /// Errors that can occur during readability parsing
 #[derive(Error, Debug)]
 pub enum ReadabilityError {
@@ -84,6 +164,14 @@ pub struct ReadabilityOptions {
     pub allowed_video_regex: Option<Regex>,
     /// Link density modifier
     pub link_density_modifier: f64,
+    /// Maximum excerpt length in characters, cut at a word boundary (0 = no limit)
+    pub excerpt_max_length: usize,
+    /// Reading speed used for `reading_time_minutes`, in words per minute
+    pub words_per_minute: usize,
+    /// Reading speed for Chinese and Japanese text, in characters per minute
+    pub cjk_chars_per_minute: usize,
+    /// Allowlist the content is sanitized against; `None` keeps it as extracted
+    pub sanitize: Option<SanitizeOptions>,
 }
 
 impl Default for ReadabilityOptions {
@@ -98,12 +186,16 @@ impl Default for ReadabilityOptions {
             disable_json_ld: false,
             allowed_video_regex: None,
             link_density_modifier: 1.0,
+            excerpt_max_length: 0,
+            words_per_minute: 230,
+            cjk_chars_per_minute: 500,
+            sanitize: None,
         }
     }
 }
 
 /// Represents an extracted article
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Article {
     /// Article title
     pub title: Option<String>,
@@ -111,20 +203,104 @@ pub struct Article {
     pub content: Option<String>,
     /// Plain text content
     pub text_content: Option<String>,
-    /// Length of the article in characters
+    /// Length of the text content in characters
     pub length: Option<usize>,
+    /// Number of words, counting each CJK character as a word
+    pub word_count: Option<usize>,
+    /// Estimated reading time in minutes
+    pub reading_time_minutes: Option<usize>,
     /// Article excerpt or description
     pub excerpt: Option<String>,
     /// Author information
     pub byline: Option<String>,
+    /// Individual authors, with profile links when the page has them
+    #[serde(default)]
+    pub authors: Vec<Author>,
     /// Content direction (ltr/rtl)
     pub dir: Option<String>,
+    /// `dir` attribute of the content or its nearest ancestor, as written
+    pub dir_attribute: Option<String>,
     /// Site name
     pub site_name: Option<String>,
-    /// Content language
+    /// Absolute URL of the image that best represents the article
+    pub lead_image: Option<String>,
+    /// Canonical URL, icons and alternate versions of the page
+    #[serde(default)]
+    pub links: ArticleLinks,
+    /// Open Graph metadata
+    pub open_graph: Option<OpenGraph>,
+    /// Twitter Card metadata
+    pub twitter_card: Option<TwitterCard>,
+    /// Paywall signals, when the page restricts access to its content
+    pub paywall: Option<Paywall>,
+    /// License the content is published under
+    pub license: Option<License>,
+    /// Bibliographic metadata of scholarly articles
+    pub citation: Option<Citation>,
+    /// All `<meta>` values by `property` or normalized `name`
+    #[serde(default)]
+    pub meta: BTreeMap<String, String>,
+    /// Content language declared by the page
     pub lang: Option<String>,
-    /// Published time
+    /// `lang` attribute of `<html>`, as written
+    pub html_lang: Option<String>,
+    /// Content language detected from the extracted text
+    pub detected_language: Option<DetectedLanguage>,
+    /// Published time, as written in the document
     pub published_time: Option<String>,
+    /// Last modified time, as written in the document
+    pub modified_time: Option<String>,
+    /// Published time parsed into a timestamp
+    pub published_at: Option<DateTime<FixedOffset>>,
+    /// Last modified time parsed into a timestamp
+    pub modified_at: Option<DateTime<FixedOffset>>,
+}
+
+impl Article {
+    /// The article with the field names and null semantics of Readability.js
+    pub fn to_mozilla(&self) -> MozillaArticle {
+        MozillaArticle::from(self)
+    }
+
+    /// The content as a typed block tree, for native rendering
+    pub fn blocks(&self) -> Vec<Block> {
+        self.content.as_deref().map(blocks::html_to_blocks).unwrap_or_default()
+    }
+
+    /// Render the article as GitHub Flavored Markdown, headed by its title
+    pub fn to_markdown(&self) -> String {
+        let mut output = String::new();
+        if let Some(title) = self.title.as_deref().filter(|title| !title.is_empty()) {
+            output.push_str(&format!("# {}\n\n", title));
+        }
+        if let Some(content) = &self.content {
+            output.push_str(&markdown::html_to_markdown(content));
+        }
+        output
+    }
+
+    /// Render the article as plain text that keeps paragraphs, list markers,
+    /// underlined headings and preformatted blocks, under its title and byline.
+    /// Paragraphs are hard-wrapped at `wrap_width` characters when given.
+    pub fn to_text(&self, wrap_width: Option<usize>) -> String {
+        let mut output = String::new();
+        if let Some(title) = self.title.as_deref().filter(|title| !title.is_empty()) {
+            output.push_str(&text::underline(title, 1));
+            output.push_str("\n\n");
+        }
+        if let Some(byline) = self.byline.as_deref().filter(|byline| !byline.is_empty()) {
+            output.push_str(&format!("By {}\n\n", byline));
+        }
+        match (&self.content, &self.text_content) {
+            (Some(content), _) => output.push_str(&text::html_to_text(content, wrap_width)),
+            (None, Some(text_content)) => {
+                output.push_str(text_content);
+                output.push('\n');
+            }
+            (None, None) => {}
+        }
+        output
+    }
 }
 
 /// The main Readability parser
@@ -134,9 +310,15 @@ pub struct Readability {
     base_uri: Option<String>,
     article_title: Option<String>,
     article_byline: Option<String>,
-    article_dir: Option<String>,
+    article_authors: Vec<Author>,
     article_site_name: Option<String>,
-    metadata: HashMap<String, String>,
+    article_lang: Option<String>,
+    article_links: ArticleLinks,
+    article_published_time: Option<String>,
+    article_modified_time: Option<String>,
+    metadata: BTreeMap<String, String>,
+    /// schema.org article from JSON-LD, completed from microdata and RDFa
+    structured_data: Option<Value>,
 }
 
 impl Readability {
@@ -151,9 +333,14 @@ impl Readability {
             base_uri: None,
             article_title: None,
             article_byline: None,
-            article_dir: None,
+            article_authors: Vec::new(),
             article_site_name: None,
-            metadata: HashMap::new(),
+            article_lang: None,
+            article_links: ArticleLinks::default(),
+            article_published_time: None,
+            article_modified_time: None,
+            metadata: BTreeMap::new(),
+            structured_data: None,
         })
     }
 
@@ -182,11 +369,27 @@ impl Readability {
         // Get article title
         self.get_article_title();
 
+        // Find the byline and take it out of the content
+        let author_links = self.get_author_links();
+        self.grab_byline();
+        self.get_article_authors(author_links);
+
         // Try to grab the article content
         let article_content = self.grab_article()?;
         let content_html = article_content.inner_html();
         let text_content = self.get_inner_text_from_ref(&article_content, true);
-        let text_length = text_content.len();
+        let text_length = get_char_count(&text_content, None);
+        let word_count = count_words(&text_content);
+        let reading_time = reading_time_minutes(
+            &text_content,
+            self.options.words_per_minute,
+            self.options.cjk_chars_per_minute,
+        );
+        let dir_attribute = self.get_dir_attribute(&article_content);
+        let dir = self.get_article_dir(&article_content, &text_content);
+        let detected_language = detect_language(&text_content);
+        let lead_image = self.get_lead_image(&article_content);
+        let paywall = paywall::detect_paywall(&self.document, self.structured_data.as_ref(), &text_content);
 
         // Check if content meets minimum requirements
         if text_length < self.options.char_threshold {
@@ -209,17 +412,50 @@ impl Readability {
             println!("Post-processing content...");
         }
 
+        let content_html = match &self.options.sanitize {
+            Some(sanitize_options) => {
+                let mut sanitize_options = sanitize_options.clone();
+                if sanitize_options.allowed_video_regex.is_none() {
+                    sanitize_options.allowed_video_regex = self.options.allowed_video_regex.clone();
+                }
+                sanitize_html(&content_html, &sanitize_options)
+            }
+            None => content_html,
+        };
+
         Some(Article {
             title: self.article_title.clone(),
             content: Some(content_html),
             text_content: Some(text_content),
             length: Some(text_length),
-            excerpt: self.metadata.get("description").cloned(),
+            word_count: Some(word_count),
+            reading_time_minutes: Some(reading_time),
+            excerpt: self.get_excerpt(&article_content),
             byline: self.article_byline.clone(),
-            dir: self.article_dir.clone(),
+            authors: self.article_authors.clone(),
+            dir,
+            dir_attribute,
             site_name: self.article_site_name.clone(),
-            lang: self.metadata.get("lang").cloned(),
-            published_time: self.metadata.get("publishedTime").cloned(),
+            lead_image,
+            links: self.article_links.clone(),
+            open_graph: social::extract_open_graph(&self.document),
+            twitter_card: social::extract_twitter_card(&self.document),
+            paywall,
+            license: license::extract_license(
+                &self.document,
+                self.structured_data.as_ref(),
+                &self.metadata,
+                self.base_uri.as_deref(),
+            ),
+            citation: citation::extract_citation(&self.document),
+            meta: self.metadata.clone(),
+            lang: self.article_lang.clone(),
+            html_lang: self.document.root_element().value().attr("lang").map(str::to_string),
+            detected_language,
+            published_at: self.article_published_time.as_deref().and_then(parse_date),
+            modified_at: self.article_modified_time.as_deref().and_then(parse_date),
+            published_time: self.article_published_time.clone(),
+            modified_time: self.article_modified_time.clone(),
         })
     }
 
@@ -228,8 +464,8 @@ impl Readability {
         // Remove excessive whitespace
         let cleaned_text = text_content.trim();
         
-        // Check for minimum word count
-        let word_count = cleaned_text.split_whitespace().count();
+        // Check for minimum word count, counting CJK characters as words
+        let word_count = count_words(cleaned_text);
         if word_count < 25 {  // Minimum 25 words for substantial content
             return false;
         }
@@ -260,106 +496,519 @@ impl Readability {
     }
 
     fn get_article_metadata(&mut self) {
-        // Extract metadata from meta tags, JSON-LD, etc.
+        // Extract metadata from meta tags, JSON-LD, microdata, etc.
         let meta_selector = Selector::parse("meta").unwrap();
         
         for element in self.document.select(&meta_selector) {
+            let content = match element.value().attr("content") {
+                Some(content) => unescape_html_entities(content.trim()),
+                None => continue,
+            };
+
             if let Some(property) = element.value().attr("property") {
-                if let Some(content) = element.value().attr("content") {
-                    self.metadata.insert(property.to_string(), content.to_string());
-                    
-                    // Handle specific Open Graph properties
-                    match property {
-                        "og:site_name" => self.article_site_name = Some(content.to_string()),
-                        _ => {}
-                    }
+                self.metadata.insert(property.to_string(), content.clone());
+
+                // Handle specific Open Graph properties
+                if property == "og:site_name" {
+                    self.article_site_name = Some(content.clone());
                 }
             }
             if let Some(name) = element.value().attr("name") {
-                if let Some(content) = element.value().attr("content") {
-                    self.metadata.insert(name.to_string(), content.to_string());
-                    
-                    // Handle specific meta name properties
-                    match name {
-                        "author" => self.article_byline = Some(content.to_string()),
-                        _ => {}
-                    }
-                }
+                // Like Readability.js, `dc.title` and `DC:Title` are the same key
+                let name = name.trim().to_lowercase().replace('.', ":");
+                self.metadata.insert(name, content);
             }
         }
 
-        // Extract byline from DOM elements
-        self.extract_byline_from_dom();
-        
-        // Extract language from html element
-        if let Ok(html_selector) = Selector::parse("html") {
-            if let Some(html_element) = self.document.select(&html_selector).next() {
-                if let Some(lang) = html_element.value().attr("lang") {
-                    self.metadata.insert("lang".to_string(), lang.to_string());
+        if !self.options.disable_json_ld {
+            self.structured_data = json_ld::find_article_object(&self.document);
+        }
+        // Microdata and RDFa fill in whatever JSON-LD did not declare
+        self.structured_data = match (self.structured_data.take(), microdata::find_article_object(&self.document)) {
+            (Some(Value::Object(mut article)), Some(Value::Object(item))) => {
+                for (key, value) in item {
+                    article.entry(key).or_insert(value);
                 }
+                Some(Value::Object(article))
             }
+            (json_ld, item) => json_ld.or(item),
+        };
+
+        self.article_links = links::extract_links(
+            &self.document,
+            self.metadata.get("og:url").map(String::as_str),
+            self.base_uri.as_deref(),
+        );
+
+        self.get_article_dates();
+        
+        self.get_article_lang();
+    }
+
+    /// Take the declared language from `<html lang>` or, failing that, the
+    /// `content-language` header, `og:locale` or JSON-LD `inLanguage`
+    fn get_article_lang(&mut self) {
+        let html_selector = Selector::parse("html").unwrap();
+        let html_lang = self.document.select(&html_selector).next()
+            .and_then(|html| html.value().attr("lang"))
+            .and_then(normalize_language_tag);
+
+        let content_language = || {
+            let meta_selector = Selector::parse("meta[http-equiv]").unwrap();
+            self.document.select(&meta_selector)
+                .find(|meta| meta.value().attr("http-equiv")
+                    .is_some_and(|equiv| equiv.trim().eq_ignore_ascii_case("content-language")))
+                .and_then(|meta| meta.value().attr("content"))
+                // The header may list several languages
+                .and_then(|content| content.split(',').next())
+                .and_then(normalize_language_tag)
+        };
+        let og_locale = || self.metadata.get("og:locale").and_then(|locale| normalize_language_tag(locale));
+        let structured_lang = || {
+            let in_language = self.structured_data.as_ref()?.get("inLanguage")?;
+            match in_language {
+                Value::String(lang) => normalize_language_tag(lang),
+                Value::Object(_) => json_ld::get_string(in_language, "alternateName")
+                    .and_then(|lang| normalize_language_tag(&lang)),
+                _ => None,
+            }
+        };
+
+        self.article_lang = html_lang
+            .or_else(content_language)
+            .or_else(og_locale)
+            .or_else(structured_lang);
+    }
+
+    /// Walk the document in order, like the node loop of Readability.js
+    /// `_grabArticle`, and take the first byline it meets out of the content.
+    /// Hidden and unlikely subtrees (comments, sidebars...) are not entered.
+    fn grab_byline(&mut self) {
+        let body_selector = Selector::parse("body").unwrap();
+        let body = match self.document.select(&body_selector).next() {
+            Some(body) => body,
+            None => return,
+        };
+
+        let mut byline = None;
+        let mut stack = vec![body.id()];
+        while let Some(node_id) = stack.pop() {
+            let element = match self.document.tree.get(node_id).and_then(ElementRef::wrap) {
+                Some(element) => element,
+                None => continue,
+            };
+
+            if !is_node_visible(&element) {
+                continue;
+            }
+
+            let tag_name = element.value().name();
+            let match_string = format!("{} {}",
+                element.value().attr("class").unwrap_or(""),
+                element.value().attr("id").unwrap_or(""));
+
+            if tag_name != "body" && tag_name != "a"
+                && is_unlikely_candidate(&match_string)
+                && !has_ancestor_tag(&element, "table", None, None)
+                && !has_ancestor_tag(&element, "code", None, None)
+            {
+                continue;
+            }
+
+            if element.value().attr("role").is_some_and(|role| UNLIKELY_ROLES.contains(&role)) {
+                continue;
+            }
+
+            if let Some(text) = self.get_valid_byline(&element, &match_string) {
+                byline = Some((node_id, text));
+                break;
+            }
+
+            // Visit children in document order
+            stack.extend(element.children().map(|child| child.id()).collect::<Vec<_>>().into_iter().rev());
         }
+
+        let dom_byline = byline.map(|(node_id, text)| {
+            if let Some(mut node) = self.document.tree.get_mut(node_id) {
+                node.detach();
+            }
+            text
+        });
+
+        self.article_byline = dom_byline.or_else(|| self.get_metadata_byline());
     }
 
-    fn extract_byline_from_dom(&mut self) {
-        // If we already have a byline from meta tags, use that
-        if self.article_byline.is_some() {
-            return;
+    /// Port of Readability.js `_isValidByline`, returning the cleaned byline text
+    fn get_valid_byline(&self, element: &ElementRef, match_string: &str) -> Option<String> {
+        let is_author_link = element.value().attr("rel") == Some("author");
+        let has_author_itemprop = element.value().attr("itemprop")
+            .is_some_and(|itemprop| itemprop.contains("author"));
+
+        if !is_author_link && !has_author_itemprop && !is_byline(match_string) {
+            return None;
         }
 
-        // Look for byline in common patterns
-        let byline_selectors = [
-            ".byline",
-            ".author",
-            ".post-author", 
-            ".article-author",
-            "[rel=\"author\"]",
-            ".by-author",
-            ".writer",
-        ];
+        // Prefer the author's name when it is marked up inside the byline
+        let name_selector = Selector::parse(r#"[itemprop~="name"]"#).unwrap();
+        let text = element.select(&name_selector).next()
+            .map(|name| self.get_inner_text_from_ref(&name, true))
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| self.get_inner_text_from_ref(element, true));
 
-        for selector_str in &byline_selectors {
-            if let Ok(selector) = Selector::parse(selector_str) {
-                if let Some(element) = self.document.select(&selector).next() {
-                    let byline_text = self.get_inner_text_from_ref(&element, false);
-                    let cleaned_byline = byline_text.trim();
-                    
-                    // Clean up common prefixes
-                    let cleaned_byline = cleaned_byline
-                        .strip_prefix("By ")
-                        .or_else(|| cleaned_byline.strip_prefix("by "))
-                        .or_else(|| cleaned_byline.strip_prefix("BY "))
-                        .or_else(|| cleaned_byline.strip_prefix("Author: "))
-                        .or_else(|| cleaned_byline.strip_prefix("Written by "))
-                        .unwrap_or(cleaned_byline);
-
-                    if !cleaned_byline.is_empty() && cleaned_byline.len() < 100 {
-                        self.article_byline = Some(cleaned_byline.to_string());
-                        break;
+        let text = clean_byline(&text);
+        if text.is_empty() || text.chars().count() >= 100 {
+            return None;
+        }
+        Some(text)
+    }
+
+    /// Byline declared in meta tags or structured data, used when the page has none
+    fn get_metadata_byline(&self) -> Option<String> {
+        BYLINE_KEYS.iter()
+            .filter_map(|key| self.metadata.get(*key))
+            // `article:author` is often a profile URL rather than a name
+            .find(|value| !value.is_empty() && !is_url(value))
+            .cloned()
+            .or_else(|| {
+                let authors = authors::from_json_ld(self.structured_data.as_ref()?);
+                let names: Vec<_> = authors.into_iter().map(|author| author.name).collect();
+                if names.is_empty() { None } else { Some(names.join(", ")) }
+            })
+    }
+
+    /// Find the first non-empty `dir` attribute of the content or its
+    /// ancestors, untouched, as Readability.js reports it
+    fn get_dir_attribute(&self, article_content: &ElementRef) -> Option<String> {
+        std::iter::once(*article_content)
+            .chain(get_node_ancestors(article_content, None))
+            .find_map(|element| element.value().attr("dir").filter(|dir| !dir.is_empty()))
+            .map(str::to_string)
+    }
+
+    /// Take the direction from the `dir` attribute of the content or its
+    /// nearest ancestor, up to `<html>`. Pages that do not declare one but are
+    /// mostly written in a right-to-left script are reported as "rtl".
+    fn get_article_dir(&self, article_content: &ElementRef, text_content: &str) -> Option<String> {
+        std::iter::once(*article_content)
+            .chain(get_node_ancestors(article_content, None))
+            .find_map(|element| element.value().attr("dir"))
+            .map(|dir| dir.trim().to_lowercase())
+            .filter(|dir| !dir.is_empty())
+            .or_else(|| {
+                if is_mostly_rtl(text_content) { Some("rtl".to_string()) } else { None }
+            })
+    }
+
+    /// Find the lead image in metadata or JSON-LD, falling back to the first
+    /// large enough image of the extracted content
+    fn get_lead_image(&self, article_content: &ElementRef) -> Option<String> {
+        let structured_image = || {
+            let image_url = |image: &Value| match image {
+                Value::String(url) => Some(url.trim().to_string()),
+                Value::Object(_) => json_ld::get_string(image, "url")
+                    .or_else(|| json_ld::get_string(image, "contentUrl")),
+                _ => None,
+            };
+            match self.structured_data.as_ref()?.get("image")? {
+                Value::Array(images) => images.iter().find_map(image_url),
+                image => image_url(image),
+            }
+        };
+
+        let url = LEAD_IMAGE_KEYS.iter()
+            .filter_map(|key| self.metadata.get(*key))
+            .find(|url| !url.is_empty())
+            .cloned()
+            .or_else(structured_image)
+            .filter(|url| !url.is_empty())
+            .or_else(|| self.get_content_lead_image(article_content))?;
+
+        Some(match &self.base_uri {
+            Some(base_uri) => to_absolute_uri(&url, base_uri),
+            None => url,
+        })
+    }
+
+    /// First content image known to be large enough, or else the first image
+    /// whose size is not known to be too small
+    fn get_content_lead_image(&self, article_content: &ElementRef) -> Option<String> {
+        let img_selector = Selector::parse("img").unwrap();
+        let mut unknown_size = None;
+
+        for img in article_content.select(&img_selector) {
+            let attr = |name: &str| img.value().attr(name).map(str::trim).filter(|v| !v.is_empty());
+            let srcset = attr("srcset").or_else(|| attr("data-srcset")).map(parse_srcset).unwrap_or_default();
+
+            // The widest srcset candidate makes the best thumbnail; without
+            // width descriptors, the one for the densest screens does
+            let widest = srcset.iter().filter(|c| c.width.is_some()).max_by_key(|c| c.width);
+            let densest = srcset.iter()
+                .filter(|c| c.density.is_some_and(|density| density > 1.0))
+                .max_by(|a, b| a.density.partial_cmp(&b.density).unwrap_or(std::cmp::Ordering::Equal));
+            let src = attr("src")
+                .or_else(|| attr("data-src"))
+                .filter(|src| !src.starts_with("data:"))
+                .map(str::to_string);
+            let best = widest.or(densest);
+            let url = match best.map(|c| c.url.clone()).or(src).or_else(|| srcset.first().map(|c| c.url.clone())) {
+                Some(url) => url,
+                None => continue,
+            };
+
+            // A density candidate has that many times the pixels of the
+            // displayed size
+            let scale = |size: u32| match (widest, densest) {
+                (None, Some(densest)) => (size as f64 * densest.density.unwrap_or(1.0)) as u32,
+                _ => size,
+            };
+            let width = widest.and_then(|c| c.width).or_else(|| attr("width").and_then(parse_dimension).map(scale));
+            let height = attr("height").and_then(parse_dimension).map(scale);
+
+            match (width, height) {
+                (None, None) => {
+                    if unknown_size.is_none() {
+                        unknown_size = Some(url);
+                    }
+                }
+                (width, height) => {
+                    let wide_enough = !matches!(width, Some(w) if w < LEAD_IMAGE_MIN_WIDTH);
+                    let tall_enough = !matches!(height, Some(h) if h < LEAD_IMAGE_MIN_HEIGHT);
+                    if wide_enough && tall_enough {
+                        return Some(url);
                     }
                 }
             }
         }
+
+        unknown_size
+    }
+
+    /// Authors linked with `rel=author`, outside of comment sections and the like
+    fn get_author_links(&self) -> Vec<Author> {
+        let link_selector = Selector::parse(r#"a[rel~="author"]"#).unwrap();
+
+        self.document.select(&link_selector)
+            .filter(|link| {
+                !get_node_ancestors(link, None).iter().any(|ancestor| {
+                    let match_string = format!("{} {}",
+                        ancestor.value().attr("class").unwrap_or(""),
+                        ancestor.value().attr("id").unwrap_or(""));
+                    is_unlikely_candidate(&match_string) || !is_node_visible(ancestor)
+                })
+            })
+            .filter_map(|link| {
+                let name = clean_byline(&self.get_inner_text_from_ref(&link, true));
+                if name.is_empty() {
+                    return None;
+                }
+                let url = link.value().attr("href").map(|href| match &self.base_uri {
+                    Some(base_uri) => to_absolute_uri(href.trim(), base_uri),
+                    None => href.trim().to_string(),
+                });
+                Some(Author { name, url, role: None })
+            })
+            .collect()
+    }
+
+    /// Build the author list from structured data, author links and the byline text
+    fn get_article_authors(&mut self, author_links: Vec<Author>) {
+        let structured_authors = self.structured_data.as_ref()
+            .map(authors::from_json_ld)
+            .unwrap_or_default();
+        let byline_authors = self.article_byline.as_deref()
+            .map(|byline| authors::split_byline(byline).iter().map(|name| Author::new(name)).collect())
+            .unwrap_or_default();
+
+        self.article_authors = authors::merge_authors(vec![structured_authors, author_links, byline_authors]);
+    }
+
+    /// Use the meta description or, like Readability.js, fall back to the
+    /// first meaningful paragraph of the extracted content
+    fn get_excerpt(&self, article_content: &ElementRef) -> Option<String> {
+        let excerpt = self.metadata.get("description")
+            .filter(|description| !description.is_empty())
+            .cloned()
+            .or_else(|| {
+                let paragraph_selector = Selector::parse("p").unwrap();
+                let paragraphs: Vec<String> = article_content.select(&paragraph_selector)
+                    .map(|p| self.get_inner_text_from_ref(&p, true))
+                    .filter(|text| !text.is_empty())
+                    .collect();
+
+                // Skip datelines, captions and other short fragments when we can
+                paragraphs.iter()
+                    .find(|text| text.chars().count() >= EXCERPT_MIN_PARAGRAPH_LENGTH)
+                    .or_else(|| paragraphs.first())
+                    .cloned()
+            })?;
+
+        if self.options.excerpt_max_length > 0 {
+            Some(truncate_at_word_boundary(&excerpt, self.options.excerpt_max_length))
+        } else {
+            Some(excerpt)
+        }
+    }
+
+    fn get_article_dates(&mut self) {
+        let structured_date = |key: &str| self.structured_data.as_ref().and_then(|article| json_ld::get_string(article, key));
+        let meta_date = |keys: &[&str]| keys.iter()
+            .filter_map(|key| self.metadata.get(*key))
+            .find(|value| !value.is_empty())
+            .cloned();
+
+        let published_time = structured_date("datePublished")
+            .or_else(|| meta_date(PUBLISHED_TIME_KEYS))
+            .or_else(|| self.find_time_element("datePublished"));
+        let modified_time = structured_date("dateModified")
+            .or_else(|| meta_date(MODIFIED_TIME_KEYS))
+            .or_else(|| self.find_time_element("dateModified"));
+
+        self.article_published_time = published_time;
+        self.article_modified_time = modified_time;
+    }
+
+    /// Find the `datetime` of a `<time>` element marked with the given
+    /// schema.org property or, failing that, one that sits next to the byline
+    fn find_time_element(&self, itemprop: &str) -> Option<String> {
+        let time_selector = Selector::parse("time[datetime]").unwrap();
+        let times: Vec<_> = self.document.select(&time_selector).collect();
+        let datetime = |time: &ElementRef| time.value().attr("datetime")
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string);
+
+        if let Some(time) = times.iter().find(|time| {
+            time.value().attr("itemprop").is_some_and(|prop| prop.split_whitespace().any(|p| p == itemprop))
+        }) {
+            return datetime(time);
+        }
+
+        // Only the publication date is ever shown next to the byline
+        if itemprop != "datePublished" {
+            return None;
+        }
+
+        times.iter()
+            .find(|time| {
+                time.value().attr("pubdate").is_some()
+                    || get_node_ancestors(time, Some(3)).iter().any(|ancestor| {
+                        let match_string = format!("{} {}",
+                            ancestor.value().attr("class").unwrap_or(""),
+                            ancestor.value().attr("id").unwrap_or(""));
+                        is_byline(&match_string)
+                    })
+            })
+            .and_then(datetime)
     }
 
     fn get_article_title(&mut self) {
-        let title_selector = Selector::parse("title").unwrap();
-        if let Some(title_element) = self.document.select(&title_selector).next() {
-            self.article_title = Some(title_element.inner_html());
+        // Titles declared in metadata are written for sharing and are usually
+        // cleaner than the <title> element
+        let metadata_title = METADATA_TITLE_KEYS.iter()
+            .filter_map(|key| self.metadata.get(*key))
+            .find(|title| !title.is_empty())
+            .cloned();
+
+        self.article_title = self.get_structured_title().or(metadata_title).or_else(|| {
+            let title = self.get_document_title();
+            if title.is_empty() { None } else { Some(title) }
+        });
+    }
+
+    /// Title from the structured data article. Like Readability.js, when
+    /// `name` and `headline` differ the one matching the document title wins.
+    fn get_structured_title(&self) -> Option<String> {
+        let article = self.structured_data.as_ref()?;
+        let name = json_ld::get_string(article, "name").filter(|name| !name.is_empty());
+        let headline = json_ld::get_string(article, "headline").filter(|headline| !headline.is_empty());
+
+        match (name, headline) {
+            (Some(name), Some(headline)) if name != headline => {
+                let title = self.get_document_title();
+                let name_matches = text_similarity(&name, &title) > 0.75;
+                let headline_matches = text_similarity(&headline, &title) > 0.75;
+                if headline_matches && !name_matches { Some(headline) } else { Some(name) }
+            }
+            (name, headline) => name.or(headline),
         }
+    }
 
-        // Try to get a better title from h1 elements
-        let h1_selector = Selector::parse("h1").unwrap();
-        for h1 in self.document.select(&h1_selector) {
-            let h1_text = self.get_inner_text_from_ref(&h1, false);
-            if h1_text.len() > 10 {
-                self.article_title = Some(h1_text);
-                break;
+    /// Port of Readability.js `_getArticleTitle`: derive the article title from
+    /// the document <title>, stripping site names and section hierarchies
+    fn get_document_title(&self) -> String {
+        let regexps = regexps::get_regexps();
+
+        let title_selector = Selector::parse("title").unwrap();
+        let orig_title = match self.document.select(&title_selector).next() {
+            Some(title_element) => self.get_inner_text_from_ref(&title_element, true),
+            None => return String::new(),
+        };
+
+        let mut cur_title = orig_title.clone();
+        let mut had_hierarchical_separators = false;
+
+        if regexps.title_separator.is_match(&cur_title) {
+            had_hierarchical_separators = regexps.title_hierarchical_separator.is_match(&cur_title);
+
+            // Remove the final part, which is usually the site name
+            if let Some(last_separator) = regexps.title_separator.find_iter(&orig_title).last() {
+                cur_title = orig_title[..last_separator.start()].to_string();
+            }
+
+            // If the resulting title is too short, remove the first part instead
+            if word_count(&cur_title) < 3 {
+                cur_title = regexps.title_first_part.replace(&orig_title, "").to_string();
+            }
+        } else if orig_title.contains(": ") {
+            // Like `indexOf(":")`, the first colon need not be followed by a space
+            let first_colon = orig_title.find(':').unwrap();
+            let last_colon = orig_title.rfind(':').unwrap();
+
+            // A heading containing this exact string means it is the full title
+            let heading_selector = Selector::parse("h1, h2").unwrap();
+            let trimmed_title = cur_title.trim();
+            let has_matching_heading = self.document.select(&heading_selector)
+                .any(|heading| heading.text().collect::<String>().trim() == trimmed_title);
+
+            if !has_matching_heading {
+                cur_title = orig_title[last_colon + 1..].to_string();
+
+                if word_count(&cur_title) < 3 {
+                    // If the title is now too short, try the first colon instead
+                    cur_title = orig_title[first_colon + 1..].to_string();
+                } else if word_count(&orig_title[..first_colon]) > 5 {
+                    // Too many words before the colon, something is weird
+                    // with the title so just use the original one
+                    cur_title = orig_title.clone();
+                }
+            }
+        } else {
+            let title_length = cur_title.chars().count();
+            if !(15..=150).contains(&title_length) {
+                let h1_selector = Selector::parse("h1").unwrap();
+                let h1s: Vec<_> = self.document.select(&h1_selector).collect();
+                if h1s.len() == 1 {
+                    cur_title = self.get_inner_text_from_ref(&h1s[0], true);
+                }
             }
         }
+
+        let cur_title = normalize_whitespace(&cur_title);
+
+        // With 4 words or fewer left, use the original title unless the
+        // only thing removed was one segment of a hierarchical title
+        let cur_word_count = word_count(&cur_title);
+        let orig_word_count = word_count(&regexps.title_separator.replace_all(&orig_title, ""));
+        if cur_word_count <= 4
+            && (!had_hierarchical_separators || cur_word_count + 1 != orig_word_count)
+        {
+            return orig_title;
+        }
+
+        cur_title
     }
 
-    fn grab_article(&self) -> Option<ElementRef> {
+    fn grab_article(&self) -> Option<ElementRef<'_>> {
         // This is the main content extraction logic
         // For now, we'll use a simplified approach
         
@@ -409,92 +1058,19 @@ impl Readability {
     }
 }
 
-/// Check if a document is likely to be readable/parseable
-pub fn is_probably_readerable(html: &str, options: Option<ReadabilityOptions>) -> bool {
-    let document = Html::parse_document(html);
-    let opts = options.unwrap_or_default();
-    
-    // Scale minimum score based on char_threshold
-    let min_content_length = if opts.char_threshold > 0 { 
-        opts.char_threshold 
-    } else { 
-        140  // Default fallback
-    };
-    
-    // Scale min_score based on char_threshold - lower thresholds need lower scores
-    let min_score = if min_content_length <= 50 {
-        10.0  // Very lenient for short content
-    } else if min_content_length <= 100 {
-        15.0  // Moderate for medium content
-    } else {
-        20.0  // Standard for longer content
-    };
-    
-    // Look for content-bearing elements
-    let content_selectors = ["p", "pre", "article", "div"];
-    let mut score = 0.0;
-    let mut total_text_length = 0;
-    
-    for selector_str in &content_selectors {
-        if let Ok(selector) = Selector::parse(selector_str) {
-            for element in document.select(&selector) {
-                let text_content = element.text().collect::<String>();
-                let text_length = text_content.trim().len();
-                
-                if text_length < 10 {  // Skip very short elements (reduced from 25)
-                    continue;
-                }
-                
-                total_text_length += text_length;
-                
-                // Check for unlikely candidates
-                let class_and_id = format!("{} {}", 
-                    element.value().attr("class").unwrap_or(""),
-                    element.value().attr("id").unwrap_or("")
-                );
-                
-                if is_unlikely_candidate(&class_and_id) {
-                    score -= 5.0;  // Penalize unlikely candidates
-                    continue;
-                }
-                
-                // Score based on element type and content length
-                let element_score = match element.value().name() {
-                    "article" => (text_length as f64 * 0.5).min(30.0),
-                    "p" => (text_length as f64 * 0.3).min(20.0),
-                    "pre" => (text_length as f64 * 0.4).min(25.0),
-                    "div" => {
-                        // More lenient for divs when using low thresholds
-                        if min_content_length <= 50 && text_length > 20 {
-                            (text_length as f64 * 0.25).min(15.0)
-                        } else if text_length > 80 {
-                            (text_length as f64 * 0.2).min(15.0)
-                        } else {
-                            0.0
-                        }
-                    },
-                    _ => 0.0,
-                };
-                
-                score += element_score;
-                
-                // Early return if we have enough score
-                if score > min_score && total_text_length >= min_content_length {
-                    return true;
-                }
-            }
-        }
-    }
-    
-    // Final check: require both minimum score and minimum content length
-    score > min_score && total_text_length >= min_content_length
+/// Strip the "By" style prefixes sites put in front of author names
+fn clean_byline(byline: &str) -> String {
+    let byline = byline.trim();
+    let byline = ["By ", "by ", "BY ", "Author: ", "Written by "].iter()
+        .find_map(|prefix| byline.strip_prefix(prefix))
+        .unwrap_or(byline);
+    byline.trim().to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::{fs, path::Path};
-    use serde_json;
 
     // Helper function to create a readability parser
     fn create_parser(html: &str) -> Readability {
@@ -507,6 +1083,7 @@ mod tests {
     }
 
     // Mozilla test case structure
+    #[allow(dead_code)]
     #[derive(Debug)]
     struct TestCase {
         name: String,
@@ -515,6 +1092,7 @@ mod tests {
         expected_metadata: TestMetadata,
     }
 
+    #[allow(dead_code)]
     #[derive(Debug, Deserialize)]
     struct TestMetadata {
         title: Option<String>,
@@ -574,12 +1152,12 @@ mod tests {
     #[test]
     fn test_readability_options_default() {
         let options = ReadabilityOptions::default();
-        assert_eq!(options.debug, false);
+        assert!(!options.debug);
         assert_eq!(options.max_elems_to_parse, 0);
         assert_eq!(options.nb_top_candidates, 5);
         assert_eq!(options.char_threshold, 500);
         assert_eq!(options.classes_to_preserve.len(), 0);
-        assert_eq!(options.keep_classes, false);
+        assert!(!options.keep_classes);
     }
 
     #[test]
@@ -591,10 +1169,9 @@ mod tests {
             length: Some(12),
             excerpt: Some("Test excerpt".to_string()),
             byline: Some("Test Author".to_string()),
-            dir: None,
             site_name: Some("Test Site".to_string()),
             lang: Some("en".to_string()),
-            published_time: None,
+            ..Default::default()
         };
         
         assert_eq!(article.title, Some("Test Title".to_string()));
@@ -626,6 +1203,37 @@ mod tests {
         assert!(article.length.unwrap() > 100);
     }
 
+    #[test]
+    fn test_length_word_count_and_reading_time() {
+        let html = format!("<html><body><article><p>{}</p></article></body></html>", "這是一篇關於語言的文章。".repeat(60));
+        let article = create_parser(&html).parse().unwrap();
+        assert_eq!(article.length, Some(720));
+        assert_eq!(article.word_count, Some(660));
+        assert_eq!(article.reading_time_minutes, Some(2));
+
+        let html = format!("<html><body><article><p>{}</p></article></body></html>", "Words to read. ".repeat(200));
+        let options = ReadabilityOptions { words_per_minute: 100, ..Default::default() };
+        let article = Readability::new(&html, Some(options)).unwrap().parse().unwrap();
+        assert_eq!(article.word_count, Some(600));
+        assert_eq!(article.reading_time_minutes, Some(6));
+    }
+
+    #[test]
+    fn test_sanitized_content() {
+        let html = format!(
+            r#"<html><body><article><p onclick="steal()" style="color: red">{}<a href="javascript:alert(1)">link</a></p>
+            <iframe src="https://www.youtube.com/embed/abc"></iframe></article></body></html>"#,
+            "Words to read. ".repeat(200)
+        );
+        let options = ReadabilityOptions { sanitize: Some(SanitizeOptions::default()), ..Default::default() };
+        let article = Readability::new(&html, Some(options)).unwrap().parse().unwrap();
+        let content = article.content.unwrap();
+        assert!(!content.contains("onclick"));
+        assert!(!content.contains("style="));
+        assert!(!content.contains("javascript:"));
+        assert!(content.contains(r#"<iframe src="https://www.youtube.com/embed/abc">"#));
+    }
+
     #[test]
     fn test_empty_document() {
         let html = "<html><body></body></html>";
@@ -657,6 +1265,7 @@ mod tests {
                 <meta name="author" content="John Doe">
                 <meta property="og:site_name" content="Example Site">
                 <meta name="description" content="This is a test article description">
+                <meta property="article:published_time" content="2022-07-08T09:10:11-04:00">
             </head>
             <body>
                 <article>
@@ -675,55 +1284,436 @@ mod tests {
         assert_eq!(article.byline, Some("John Doe".to_string()));
         assert_eq!(article.site_name, Some("Example Site".to_string()));
         assert_eq!(article.excerpt, Some("This is a test article description".to_string()));
+        assert_eq!(article.published_time, Some("2022-07-08T09:10:11-04:00".to_string()));
+        assert_eq!(
+            article.published_at,
+            DateTime::parse_from_rfc3339("2022-07-08T09:10:11-04:00").ok()
+        );
         assert!(article.content.is_some());
     }
 
+    // Helper function to run metadata extraction on a document
+    fn extract_metadata(html: &str) -> Readability {
+        let mut parser = create_parser(html);
+        parser.get_article_metadata();
+        parser
+    }
+
+    // Helper function to run title extraction on a document
+    fn extract_title(html: &str) -> Option<String> {
+        let mut parser = extract_metadata(html);
+        parser.get_article_title();
+        parser.article_title
+    }
+
+    #[test]
+    fn test_title_separators() {
+        assert_eq!(
+            extract_title("<html><head><title>Why Rust Is Eating The World | Example News</title></head></html>"),
+            Some("Why Rust Is Eating The World".to_string())
+        );
+        // Too short once the last part is removed, so the first part goes instead
+        assert_eq!(
+            extract_title("<html><head><title>Example News - Why Rust Is Eating The World</title></head></html>"),
+            Some("Why Rust Is Eating The World".to_string())
+        );
+        // Stripping a hierarchy down to a few words falls back to the original
+        assert_eq!(
+            extract_title("<html><head><title>Section » Subsection » Great Article Name</title></head></html>"),
+            Some("Section » Subsection » Great Article Name".to_string())
+        );
+        assert_eq!(
+            extract_title("<html><head><title>Short | Site</title></head></html>"),
+            Some("Short | Site".to_string())
+        );
+    }
+
+    #[test]
+    fn test_title_colon_handling() {
+        assert_eq!(
+            extract_title("<html><head><title>Example News: Why Rust Is Eating The World</title></head></html>"),
+            Some("Why Rust Is Eating The World".to_string())
+        );
+        // A heading with the exact title keeps the colon
+        assert_eq!(
+            extract_title("<html><head><title>Rust: Why It Is Eating The World</title></head><body><h1>Rust: Why It Is Eating The World</h1></body></html>"),
+            Some("Rust: Why It Is Eating The World".to_string())
+        );
+        // Too little after the last colon falls back to the first one
+        assert_eq!(
+            extract_title("<html><head><title>News:Tech Stories Of The Week: Rust Wins</title></head></html>"),
+            Some("Tech Stories Of The Week: Rust Wins".to_string())
+        );
+    }
+
+    #[test]
+    fn test_title_h1_fallback() {
+        // Only a single h1 may replace a title that is too short
+        assert_eq!(
+            extract_title("<html><head><title>Home</title></head><body><h1>The Real Article Headline Here</h1></body></html>"),
+            Some("The Real Article Headline Here".to_string())
+        );
+        assert_eq!(
+            extract_title("<html><head><title>Home</title></head><body><h1>Site Logo Heading</h1><h1>The Real Article Headline</h1></body></html>"),
+            Some("Home".to_string())
+        );
+        // A long enough title is never replaced by a logo heading
+        assert_eq!(
+            extract_title("<html><head><title>A Reasonable Article Title</title></head><body><h1>Example Company Logo</h1></body></html>"),
+            Some("A Reasonable Article Title".to_string())
+        );
+    }
+
+    #[test]
+    fn test_title_entities_and_metadata() {
+        assert_eq!(
+            extract_title("<html><head><title>Salt &amp; Pepper Shakers Through the Ages</title></head></html>"),
+            Some("Salt & Pepper Shakers Through the Ages".to_string())
+        );
+        assert_eq!(
+            extract_title(r#"<html><head><title>Home | Site</title><meta property="og:title" content="Tom &amp;amp; Jerry &#8212; Reunited"></head></html>"#),
+            Some("Tom & Jerry — Reunited".to_string())
+        );
+        assert_eq!(
+            extract_title(r#"<html><head><title>Home | Site</title><meta name="DC.title" content="Dublin Core Title"><meta property="og:title" content="Open Graph Title"></head></html>"#),
+            Some("Dublin Core Title".to_string())
+        );
+    }
+
     #[test]
-    fn test_is_probably_readerable_basic() {
-        let readerable_html = r#"
+    fn test_dates_from_meta_and_json_ld() {
+        let parser = extract_metadata(r#"<html><head>
+            <meta property="article:published_time" content="2021-03-04T05:06:07+01:00">
+            <meta property="article:modified_time" content="2021-03-05T00:00:00Z">
+            </head></html>"#);
+        assert_eq!(parser.article_published_time.as_deref(), Some("2021-03-04T05:06:07+01:00"));
+        assert_eq!(parser.article_modified_time.as_deref(), Some("2021-03-05T00:00:00Z"));
+
+        let parser = extract_metadata(r#"<html><head>
+            <meta property="article:published_time" content="2021-03-04">
+            <script type="application/ld+json">{"@context": "https://schema.org", "@type": "NewsArticle",
+                "datePublished": "2020-01-02T03:04:05Z", "dateModified": "2020-01-03"}</script>
+            </head></html>"#);
+        assert_eq!(parser.article_published_time.as_deref(), Some("2020-01-02T03:04:05Z"));
+        assert_eq!(parser.article_modified_time.as_deref(), Some("2020-01-03"));
+    }
+
+    #[test]
+    fn test_structured_title_and_microdata() {
+        assert_eq!(
+            extract_title(r#"<html><head><title>Home | Site</title><meta property="og:title" content="Open Graph Title">
+                <script type="application/ld+json">{"@context": "https://schema.org", "@type": "Article",
+                "headline": "JSON-LD Headline"}</script></head></html>"#),
+            Some("JSON-LD Headline".to_string())
+        );
+
+        // JSON-LD takes precedence, microdata fills in what it lacks
+        let mut parser = extract_metadata(r#"<html><head>
+            <script type="application/ld+json">{"@context": "https://schema.org", "@type": "NewsArticle",
+                "datePublished": "2020-01-02"}</script>
+            </head><body><article itemscope itemtype="https://schema.org/NewsArticle">
+                <h1 itemprop="headline">Microdata Headline</h1>
+                <meta itemprop="datePublished" content="2019-01-01">
+                <meta itemprop="dateModified" content="2019-02-02">
+                <span itemprop="author" itemscope itemtype="https://schema.org/Person"><span itemprop="name">Jane Doe</span></span>
+            </article></body></html>"#);
+        assert_eq!(parser.article_published_time.as_deref(), Some("2020-01-02"));
+        assert_eq!(parser.article_modified_time.as_deref(), Some("2019-02-02"));
+        assert_eq!(parser.get_metadata_byline().as_deref(), Some("Jane Doe"));
+        parser.get_article_title();
+        assert_eq!(parser.article_title.as_deref(), Some("Microdata Headline"));
+    }
+
+    #[test]
+    fn test_dates_from_time_elements() {
+        let parser = extract_metadata(r#"<html><body>
+            <aside><time datetime="2019-12-31">Sidebar date</time></aside>
+            <div class="byline"><span>By Jane</span> <span><time datetime="2020-05-06T07:08:09Z">May 6</time></span></div>
+            </body></html>"#);
+        assert_eq!(parser.article_published_time.as_deref(), Some("2020-05-06T07:08:09Z"));
+        assert_eq!(parser.article_modified_time, None);
+
+        let parser = extract_metadata(r#"<html><body>
+            <time itemprop="dateModified" datetime="2020-05-07">Updated</time>
+            <time itemprop="datePublished" datetime="2020-05-06">Published</time>
+            </body></html>"#);
+        assert_eq!(parser.article_published_time.as_deref(), Some("2020-05-06"));
+        assert_eq!(parser.article_modified_time.as_deref(), Some("2020-05-07"));
+    }
+
+    #[test]
+    fn test_excerpt_falls_back_to_first_paragraph() {
+        let html = r#"
             <html>
+            <head><title>Excerpt Test Article</title></head>
             <body>
                 <article>
-                    <h1>Article Title</h1>
-                    <p>This is a substantial article with enough content to be considered readerable. Lorem ipsum dolor sit amet, consectetur adipiscing elit. Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum. Sed ut perspiciatis unde omnis iste natus error sit voluptatem accusantium doloremque laudantium.</p>
+                    <p>  May 5, 2021  </p>
+                    <p>The first   real paragraph
+                       of this article is used as the excerpt when the page has no description.</p>
+                    <p>This is the main content of the article with sufficient length to meet the character threshold requirements for proper readability parsing. Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur.</p>
                 </article>
             </body>
             </html>
         "#;
 
-        let non_readerable_html = r#"
+        let article = create_parser(html).parse().unwrap();
+        assert_eq!(
+            article.excerpt,
+            Some("The first real paragraph of this article is used as the excerpt when the page has no description.".to_string())
+        );
+
+        let mut parser = Readability::new(html, Some(ReadabilityOptions {
+            excerpt_max_length: 30,
+            ..Default::default()
+        })).unwrap();
+        let article = parser.parse().unwrap();
+        assert_eq!(article.excerpt, Some("The first real paragraph of…".to_string()));
+    }
+
+    const LONG_PARAGRAPH: &str = "This is the main content of the article with sufficient length to meet the character threshold requirements for proper readability parsing. Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum.";
+
+    #[test]
+    fn test_byline_detected_and_removed_from_content() {
+        let html = format!(r#"
             <html>
+            <head><meta name="author" content="Meta Author"></head>
             <body>
-                <div class="navigation">Menu</div>
-                <div class="sidebar">Ads</div>
-                <p>Short content</p>
+                <article>
+                    <h1>Article Heading</h1>
+                    <p class="byline">By Jane Reporter</p>
+                    <p>{}</p>
+                    <div class="comments">
+                        <p class="author">Angry Commenter</p>
+                    </div>
+                </article>
             </body>
             </html>
-        "#;
+        "#, LONG_PARAGRAPH);
+
+        let article = create_parser(&html).parse().unwrap();
+        assert_eq!(article.byline, Some("Jane Reporter".to_string()));
+        assert!(!article.content.unwrap().contains("Jane Reporter"));
+        assert!(!article.text_content.unwrap().contains("Jane Reporter"));
+    }
+
+    #[test]
+    fn test_byline_skips_comment_sections() {
+        let html = format!(r#"
+            <html><body>
+                <article>
+                    <section id="comments"><span rel="author">Angry Commenter</span></section>
+                    <p>{}</p>
+                    <span itemprop="author" itemscope><span itemprop="name">Sam Writer</span></span>
+                </article>
+            </body></html>
+        "#, LONG_PARAGRAPH);
+
+        let article = create_parser(&html).parse().unwrap();
+        assert_eq!(article.byline, Some("Sam Writer".to_string()));
+    }
+
+    #[test]
+    fn test_byline_falls_back_to_metadata() {
+        let html = format!(r#"
+            <html><head>
+                <meta property="article:author" content="https://example.com/authors/jane">
+                <script type="application/ld+json">{{"@context": "https://schema.org", "@type": "Article",
+                    "author": [{{"@type": "Person", "name": "Jane Doe"}}, {{"@type": "Person", "name": "John Roe"}}]}}</script>
+            </head>
+            <body><article><p>{}</p></article></body></html>
+        "#, LONG_PARAGRAPH);
+
+        let article = create_parser(&html).parse().unwrap();
+        assert_eq!(article.byline, Some("Jane Doe, John Roe".to_string()));
+    }
+
+    #[test]
+    fn test_structured_authors() {
+        let html = format!(r#"
+            <html><head>
+                <script type="application/ld+json">{{"@context": "https://schema.org", "@type": "NewsArticle",
+                    "author": {{"@type": "Person", "name": "Jane Doe", "jobTitle": "Science Editor"}}}}</script>
+            </head>
+            <body><article>
+                <div class="byline">By <a rel="author" href="/people/jane">Jane Doe</a> (@janedoe),
+                    <a rel="author" href="/people/john">John Roe</a> &amp; Max Mustermann, Staff | May 5, 2021</div>
+                <p>{}</p>
+                <div class="comments"><a rel="author" href="/people/troll">Troll</a></div>
+            </article></body></html>
+        "#, LONG_PARAGRAPH);
+
+        let mut parser = Readability::new_with_base_uri(&html, "https://example.com/news/story.html", None).unwrap();
+        let article = parser.parse().unwrap();
+        assert_eq!(article.authors, vec![
+            Author {
+                name: "Jane Doe".to_string(),
+                url: Some("https://example.com/people/jane".to_string()),
+                role: Some("Science Editor".to_string()),
+            },
+            Author {
+                name: "John Roe".to_string(),
+                url: Some("https://example.com/people/john".to_string()),
+                role: None,
+            },
+            Author::new("Max Mustermann"),
+        ]);
+    }
+
+    #[test]
+    fn test_dir_from_content_ancestry() {
+        let html = format!(r#"
+            <html dir="ltr"><body>
+                <div dir="RTL"><article><p>{}</p></article></div>
+            </body></html>
+        "#, LONG_PARAGRAPH);
+        let article = create_parser(&html).parse().unwrap();
+        assert_eq!(article.dir, Some("rtl".to_string()));
+
+        let html = format!(r#"<html dir="ltr"><body><article><p>{}</p></article></body></html>"#, LONG_PARAGRAPH);
+        let article = create_parser(&html).parse().unwrap();
+        assert_eq!(article.dir, Some("ltr".to_string()));
+
+        let html = format!(r#"<html><body><article><p>{}</p></article></body></html>"#, LONG_PARAGRAPH);
+        let article = create_parser(&html).parse().unwrap();
+        assert_eq!(article.dir, None);
+    }
+
+    #[test]
+    fn test_article_links() {
+        let html = format!(r#"
+            <html><head>
+                <meta property="og:url" content="https://example.com/og">
+                <link rel="canonical" href="/news/story">
+                <link rel="icon" href="/favicon.ico">
+            </head>
+            <body><article><p>{}</p></article></body></html>
+        "#, LONG_PARAGRAPH);
+        let mut parser = Readability::new_with_base_uri(&html, "https://example.com/news/story?page=2", None).unwrap();
+        let article = parser.parse().unwrap();
+        assert_eq!(article.links.canonical, Some("https://example.com/news/story".to_string()));
+        assert_eq!(article.links.icons[0].url, "https://example.com/favicon.ico");
+    }
+
+    #[test]
+    fn test_social_metadata_on_article() {
+        let html = format!(r#"
+            <html><head>
+                <meta property="og:title" content="Open Graph Title">
+                <meta property="og:image" content="https://example.com/a.jpg">
+                <meta property="og:image:width" content="1200">
+                <meta name="twitter:card" content="summary">
+                <meta name="Description" content="A description">
+            </head>
+            <body><article><p>{}</p></article></body></html>
+        "#, LONG_PARAGRAPH);
+        let article = create_parser(&html).parse().unwrap();
+
+        let og = article.open_graph.as_ref().unwrap();
+        assert_eq!(og.title, Some("Open Graph Title".to_string()));
+        assert_eq!(og.images[0].width, Some(1200));
+        assert_eq!(article.twitter_card.as_ref().unwrap().card, Some("summary".to_string()));
+        assert_eq!(article.meta.get("description"), Some(&"A description".to_string()));
+        assert_eq!(article.meta.get("og:title"), Some(&"Open Graph Title".to_string()));
+
+        let json = serde_json::to_value(&article).unwrap();
+        assert_eq!(json["open_graph"]["type"], Value::Null);
+        assert_eq!(json["open_graph"]["images"][0]["url"], "https://example.com/a.jpg");
+    }
 
-        assert_eq!(is_probably_readerable(readerable_html, None), true);
-        assert_eq!(is_probably_readerable(non_readerable_html, None), false);
+    #[test]
+    fn test_declared_language_sources() {
+        let parser = extract_metadata(r#"<html lang="de"><head><meta property="og:locale" content="en_US"></head></html>"#);
+        assert_eq!(parser.article_lang.as_deref(), Some("de"));
+        let parser = extract_metadata(r#"<html lang=" en_us "><head></head></html>"#);
+        assert_eq!(parser.article_lang.as_deref(), Some("en-US"));
+
+        let parser = extract_metadata(r#"<html><head>
+            <meta http-equiv="Content-Language" content="fr_fr, en">
+            <meta property="og:locale" content="en_US"></head></html>"#);
+        assert_eq!(parser.article_lang.as_deref(), Some("fr-FR"));
+
+        let parser = extract_metadata(r#"<html><head><meta property="og:locale" content="pt_BR"></head></html>"#);
+        assert_eq!(parser.article_lang.as_deref(), Some("pt-BR"));
+
+        let parser = extract_metadata(r#"<html><head><script type="application/ld+json">{"@context": "https://schema.org",
+            "@type": "Article", "inLanguage": {"@type": "Language", "name": "Spanish", "alternateName": "es"}}</script></head></html>"#);
+        assert_eq!(parser.article_lang.as_deref(), Some("es"));
     }
 
     #[test]
-    fn test_is_probably_readerable_with_options() {
+    fn test_declared_and_detected_language() {
         let html = r#"
-            <html>
-            <body>
-                <p>This is a medium-length article that might be readerable with different thresholds. Lorem ipsum dolor sit amet, consectetur adipiscing elit. Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.</p>
-            </body>
-            </html>
+            <html lang="en"><body><article>
+                <p>Die Bundesregierung hat am Montag angekündigt, dass sie die geplante Reform der Rentenversicherung nicht mehr in diesem Jahr umsetzen wird. Stattdessen sollen die Gespräche mit den Ländern und den Sozialpartnern im Herbst fortgesetzt werden, wie ein Sprecher des Ministeriums in Berlin mitteilte.</p>
+                <p>Die Opposition kritisierte die Entscheidung scharf und warf der Regierung vor, die dringend notwendigen Veränderungen immer weiter zu verschieben. Auch mehrere Verbände äußerten sich enttäuscht über die Verzögerung und forderten einen verbindlichen Zeitplan für die nächsten Schritte.</p>
+            </article></body></html>
         "#;
+        let article = create_parser(html).parse().unwrap();
+        assert_eq!(article.lang, Some("en".to_string()));
+        let detected = article.detected_language.unwrap();
+        assert_eq!(detected.code, "de");
+        assert!(detected.confidence > 0.5, "confidence {}", detected.confidence);
+    }
 
-        // Default options (high threshold)
-        assert_eq!(is_probably_readerable(html, None), false);
+    #[test]
+    fn test_lead_image_from_metadata() {
+        let html = format!(r#"
+            <html><head>
+                <meta name="twitter:image" content="/images/twitter.jpg">
+                <meta property="og:image" content="/images/og.jpg">
+            </head>
+            <body><article><p>{}</p></article></body></html>
+        "#, LONG_PARAGRAPH);
+        let mut parser = Readability::new_with_base_uri(&html, "https://example.com/news/story.html", None).unwrap();
+        let article = parser.parse().unwrap();
+        assert_eq!(article.lead_image, Some("https://example.com/images/og.jpg".to_string()));
+
+        let html = format!(r#"
+            <html><head><script type="application/ld+json">{{"@context": "https://schema.org", "@type": "Article",
+                "image": [{{"@type": "ImageObject", "url": "https://cdn.example.com/hero.jpg"}}]}}</script></head>
+            <body><article><p>{}</p></article></body></html>
+        "#, LONG_PARAGRAPH);
+        let article = create_parser(&html).parse().unwrap();
+        assert_eq!(article.lead_image, Some("https://cdn.example.com/hero.jpg".to_string()));
+    }
 
-        // Lower threshold
-        let low_threshold_options = ReadabilityOptions {
-            char_threshold: 50,
-            ..Default::default()
-        };
-        assert_eq!(is_probably_readerable(html, Some(low_threshold_options)), true);
+    #[test]
+    fn test_lead_image_from_content() {
+        let html = format!(r#"
+            <html><body><article>
+                <img src="/icons/share.png" width="16" height="16">
+                <img src="/images/thin.jpg" width="800" height="20">
+                <img src="/images/small.jpg" srcset="/images/small.jpg 200w, /images/large.jpg 1200w">
+                <p>{}</p>
+            </article></body></html>
+        "#, LONG_PARAGRAPH);
+        let mut parser = Readability::new_with_base_uri(&html, "https://example.com/news/story.html", None).unwrap();
+        let article = parser.parse().unwrap();
+        assert_eq!(article.lead_image, Some("https://example.com/images/large.jpg".to_string()));
+
+        let html = format!(r#"
+            <html><body><article>
+                <img src="/icons/share.png" width="16" height="16">
+                <img src="photo.jpg">
+                <p>{}</p>
+            </article></body></html>
+        "#, LONG_PARAGRAPH);
+        let mut parser = Readability::new_with_base_uri(&html, "https://example.com/news/story.html", None).unwrap();
+        let article = parser.parse().unwrap();
+        assert_eq!(article.lead_image, Some("https://example.com/news/photo.jpg".to_string()));
+
+        // A 2x candidate of a 200x150 image has enough pixels
+        let html = format!(r#"
+            <html><body><article>
+                <img src="/images/plain.jpg" width="200" height="150">
+                <img src="/images/dense.jpg" srcset="/images/dense.jpg 1x, /images/dense@2x.jpg 2x" width="200" height="150">
+                <p>{}</p>
+            </article></body></html>
+        "#, LONG_PARAGRAPH);
+        let mut parser = Readability::new_with_base_uri(&html, "https://example.com/news/story.html", None).unwrap();
+        let article = parser.parse().unwrap();
+        assert_eq!(article.lead_image, Some("https://example.com/images/dense@2x.jpg".to_string()));
     }
 
     #[test]
@@ -769,6 +1759,8 @@ mod tests {
         assert!(article.text_content.is_some());
         // Unicode content should be preserved
         assert!(article.text_content.unwrap().contains("مقالة"));
+        // Arabic without a dir attribute is inferred to be right-to-left
+        assert_eq!(article.dir, Some("rtl".to_string()));
     }
 
     #[test]
@@ -923,49 +1915,20 @@ mod tests {
     #[test]
     fn test_mozilla_is_probably_readerable_test_cases() {
         let test_cases = load_mozilla_test_cases();
-        
+
         if test_cases.is_empty() {
             println!("No Mozilla test cases found - skipping isProbablyReaderable tests");
             return;
         }
-        
-        let mut correct_predictions = 0;
-        let mut total_predictions = 0;
-        let mut errors = Vec::new();
-        
-        for test_case in test_cases {
-            if let Some(expected_readerable) = test_case.expected_metadata.readerable {
-                let actual_readerable = is_probably_readerable(&test_case.source, None);
-                
-                if actual_readerable == expected_readerable {
-                    correct_predictions += 1;
-                } else {
-                    errors.push(format!("{}: isProbablyReaderable mismatch. Expected: {}, Got: {}", 
-                        test_case.name, expected_readerable, actual_readerable));
-                }
-                total_predictions += 1;
-            }
-        }
-        
-        println!("isProbablyReaderable test results: {}/{} correct predictions", 
-            correct_predictions, total_predictions);
-        
-        if !errors.is_empty() {
-            println!("isProbablyReaderable errors:");
-            for error in &errors[..std::cmp::min(5, errors.len())] {  // Show first 5 errors
-                println!("  {}", error);
-            }
-            if errors.len() > 5 {
-                println!("  ... and {} more errors", errors.len() - 5);
-            }
-        }
-        
-        // Require at least 70% accuracy for isProbablyReaderable
-        if total_predictions > 0 {
-            let accuracy = (correct_predictions as f64) / (total_predictions as f64);
-            assert!(accuracy > 0.7, "isProbablyReaderable accuracy too low: {:.2}% ({}/{} correct)", 
-                accuracy * 100.0, correct_predictions, total_predictions);
-        }
+
+        let mismatches: Vec<String> = test_cases.iter()
+            .filter_map(|test_case| {
+                let expected = test_case.expected_metadata.readerable?;
+                let actual = is_probably_readerable(&test_case.source, None);
+                (actual != expected).then(|| format!("{}: expected {}, got {}", test_case.name, expected, actual))
+            })
+            .collect();
+        assert!(mismatches.is_empty(), "isProbablyReaderable mismatches:\n  {}", mismatches.join("\n  "));
     }
 
     // Test case generation and verification functionality
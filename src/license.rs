@@ -0,0 +1,209 @@
+//! Content license detection for the Readability parser
+
+use std::collections::BTreeMap;
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::json_ld;
+use crate::regexps::get_regexps;
+use crate::utils::{is_url, to_absolute_uri};
+
+/// Metadata keys that may hold the license or a rights statement
+const RIGHTS_KEYS: &[&str] = &["dcterms:license", "dc:rights", "dcterms:rights", "rights"];
+
+/// SPDX identifiers of the jurisdiction ports of Creative Commons licenses
+const PORTED_CC_IDS: &[&str] = &[
+    "CC-BY-2.5-AU", "CC-BY-3.0-AT", "CC-BY-3.0-AU", "CC-BY-3.0-DE", "CC-BY-3.0-IGO",
+    "CC-BY-3.0-NL", "CC-BY-3.0-US", "CC-BY-NC-3.0-DE", "CC-BY-ND-3.0-DE", "CC-BY-NC-ND-3.0-DE",
+    "CC-BY-NC-ND-3.0-IGO", "CC-BY-NC-SA-2.0-DE", "CC-BY-NC-SA-2.0-FR", "CC-BY-NC-SA-2.0-UK",
+    "CC-BY-NC-SA-3.0-DE", "CC-BY-NC-SA-3.0-IGO", "CC-BY-SA-2.0-UK", "CC-BY-SA-2.1-JP",
+    "CC-BY-SA-3.0-AT", "CC-BY-SA-3.0-DE", "CC-BY-SA-3.0-IGO",
+];
+
+/// The license the content is published under
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct License {
+    /// License URL, in canonical form for Creative Commons licenses
+    pub url: Option<String>,
+    /// SPDX identifier, e.g. `CC-BY-SA-4.0`, when the license is recognized
+    pub spdx_id: Option<String>,
+    /// Free-form rights statement, e.g. from `dc.rights`
+    pub rights: Option<String>,
+}
+
+/// Find the content license in the structured data article, `rel=license`
+/// links, rights metadata and Creative Commons badges, in that order
+pub fn extract_license(
+    document: &Html,
+    article: Option<&Value>,
+    metadata: &BTreeMap<String, String>,
+    base_uri: Option<&str>,
+) -> Option<License> {
+    let resolve = |url: &str| match base_uri {
+        Some(base_uri) => to_absolute_uri(url, base_uri),
+        None => url.to_string(),
+    };
+
+    let rights = RIGHTS_KEYS.iter()
+        .filter_map(|key| metadata.get(*key))
+        .find(|value| !value.is_empty());
+
+    let json_ld_license = || -> Option<License> {
+        match article?.get("license")? {
+            Value::String(license) => from_reference(license, &resolve),
+            // A CreativeWork describing the license
+            license @ Value::Object(_) => json_ld::get_string(license, "url")
+                .and_then(|url| from_reference(&url, &resolve))
+                .or_else(|| from_reference(&json_ld::get_string(license, "name")?, &resolve)),
+            Value::Array(licenses) => licenses.iter()
+                .filter_map(Value::as_str)
+                .find_map(|license| from_reference(license, &resolve)),
+            _ => None,
+        }
+    };
+    let rel_license = || {
+        let license_selector = Selector::parse("a[rel~=license][href], link[rel~=license][href]").unwrap();
+        document.select(&license_selector)
+            .filter_map(|link| link.value().attr("href"))
+            .find_map(|href| from_reference(href, &resolve))
+    };
+    let metadata_license = || rights.and_then(|rights| from_reference(rights, &resolve));
+    let badge_license = || {
+        let badge_selector = Selector::parse("a[href], img[src]").unwrap();
+        document.select(&badge_selector)
+            .filter_map(|element| element.value().attr("href").or_else(|| element.value().attr("src")))
+            .find_map(|url| {
+                let spdx_id = creative_commons_id(url)?;
+                Some(License { url: creative_commons_url(&spdx_id), spdx_id: Some(spdx_id), rights: None })
+            })
+    };
+
+    let mut license = json_ld_license()
+        .or_else(rel_license)
+        .or_else(metadata_license)
+        .or_else(badge_license)
+        .unwrap_or_default();
+
+    // A rights statement that is not itself a license reference is kept as written
+    if let Some(rights) = rights.filter(|rights| !is_url(rights)) {
+        license.rights = Some(rights.clone());
+    }
+
+    if license == License::default() { None } else { Some(license) }
+}
+
+/// Interpret a license URL or name such as "CC BY-SA 4.0"
+fn from_reference(reference: &str, resolve: &dyn Fn(&str) -> String) -> Option<License> {
+    let reference = reference.trim();
+    if reference.is_empty() {
+        return None;
+    }
+
+    let spdx_id = creative_commons_id(reference);
+    let url = spdx_id.as_deref()
+        .and_then(creative_commons_url)
+        .or_else(|| if is_url(reference) || reference.starts_with('/') { Some(resolve(reference)) } else { None });
+
+    if url.is_none() && spdx_id.is_none() {
+        return None;
+    }
+    Some(License { url, spdx_id, rights: None })
+}
+
+/// SPDX identifier of a Creative Commons license URL, badge or name
+fn creative_commons_id(reference: &str) -> Option<String> {
+    let regexps = get_regexps();
+
+    if let Some(caps) = regexps.creative_commons_url.captures(reference) {
+        let mut spdx_id = format!("CC-{}-{}", caps[1].to_uppercase(), &caps[2]);
+        if let Some(jurisdiction) = caps.get(3) {
+            let ported_id = format!("{}-{}", spdx_id, jurisdiction.as_str().to_uppercase());
+            if !PORTED_CC_IDS.contains(&ported_id.as_str()) {
+                return None;
+            }
+            spdx_id = ported_id;
+        }
+        return Some(spdx_id);
+    }
+    if regexps.creative_commons_zero.is_match(reference) {
+        return Some("CC0-1.0".to_string());
+    }
+    regexps.creative_commons_name.captures(reference).map(|caps| {
+        let kind = caps[1].to_uppercase().replace(char::is_whitespace, "-");
+        format!("CC-{}-{}", kind, &caps[2])
+    })
+}
+
+/// Canonical URL of a Creative Commons license from its SPDX identifier
+fn creative_commons_url(spdx_id: &str) -> Option<String> {
+    if spdx_id == "CC0-1.0" {
+        return Some("https://creativecommons.org/publicdomain/zero/1.0/".to_string());
+    }
+    let parts: Vec<&str> = spdx_id.strip_prefix("CC-")?.split('-').collect();
+    let version_index = parts.iter().position(|part| part.contains('.'))?;
+    let mut url = format!(
+        "https://creativecommons.org/licenses/{}/{}/",
+        parts[..version_index].join("-").to_lowercase(),
+        parts[version_index],
+    );
+    if let Some(jurisdiction) = parts.get(version_index + 1) {
+        url.push_str(&jurisdiction.to_lowercase());
+        url.push('/');
+    }
+    Some(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn license_of(html: &str, metadata: &[(&str, &str)]) -> Option<License> {
+        let document = Html::parse_document(html);
+        let metadata = metadata.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect();
+        extract_license(&document, None, &metadata, Some("https://example.com/article"))
+    }
+
+    #[test]
+    fn test_creative_commons_id() {
+        assert_eq!(creative_commons_id("https://creativecommons.org/licenses/by-sa/4.0/deed.en"), Some("CC-BY-SA-4.0".to_string()));
+        assert_eq!(creative_commons_id("http://creativecommons.org/licenses/by-nc-nd/3.0/de/"), Some("CC-BY-NC-ND-3.0-DE".to_string()));
+        assert_eq!(creative_commons_id("https://i.creativecommons.org/l/by/4.0/88x31.png"), Some("CC-BY-4.0".to_string()));
+        assert_eq!(creative_commons_id("https://creativecommons.org/publicdomain/zero/1.0/"), Some("CC0-1.0".to_string()));
+        assert_eq!(creative_commons_id("Licensed under CC BY-NC 2.0"), Some("CC-BY-NC-2.0".to_string()));
+        assert_eq!(creative_commons_id("https://creativecommons.org/licenses/by/2.0/xx/"), None);
+        assert_eq!(creative_commons_id("All rights reserved"), None);
+    }
+
+    #[test]
+    fn test_rel_license_and_rights() {
+        let license = license_of(
+            r#"<html><body><footer><a rel="license" href="http://creativecommons.org/licenses/by/4.0/">CC BY</a></footer></body></html>"#,
+            &[("dc:rights", "© 2021 Example News")],
+        ).unwrap();
+        assert_eq!(license, License {
+            url: Some("https://creativecommons.org/licenses/by/4.0/".to_string()),
+            spdx_id: Some("CC-BY-4.0".to_string()),
+            rights: Some("© 2021 Example News".to_string()),
+        });
+
+        let license = license_of(r#"<html><head><link rel="license" href="/terms"></head></html>"#, &[]).unwrap();
+        assert_eq!(license.url, Some("https://example.com/terms".to_string()));
+        assert_eq!(license.spdx_id, None);
+    }
+
+    #[test]
+    fn test_json_ld_license_and_badges() {
+        let document = Html::parse_document(r#"<html><body>
+            <img src="https://licensebuttons.net/l/by-nd/4.0/88x31.png"></body></html>"#);
+        let article: Value = serde_json::from_str(r#"{"@type": "Article",
+            "license": {"@type": "CreativeWork", "name": "CC BY-SA 3.0"}}"#).unwrap();
+        let license = extract_license(&document, Some(&article), &BTreeMap::new(), None).unwrap();
+        assert_eq!(license.spdx_id, Some("CC-BY-SA-3.0".to_string()));
+        assert_eq!(license.url, Some("https://creativecommons.org/licenses/by-sa/3.0/".to_string()));
+
+        let license = extract_license(&document, None, &BTreeMap::new(), None).unwrap();
+        assert_eq!(license.spdx_id, Some("CC-BY-ND-4.0".to_string()));
+
+        assert_eq!(license_of("<html><body><p>No license.</p></body></html>", &[]), None);
+    }
+}
@@ -0,0 +1,138 @@
+//! `<link>` element metadata for the Readability parser
+
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use crate::utils::to_absolute_uri;
+
+/// Links a page declares about itself in `<link>` and `og:url`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArticleLinks {
+    /// Canonical URL from `rel=canonical`, falling back to `og:url`
+    pub canonical: Option<String>,
+    /// Accelerated Mobile Pages version from `rel=amphtml`
+    pub amp: Option<String>,
+    /// Favicons and touch icons, in document order
+    pub icons: Vec<Icon>,
+    /// Translations of the page from `rel=alternate` links with `hreflang`
+    pub alternates: Vec<AlternateLink>,
+}
+
+/// An icon declared with `rel=icon` or `rel=apple-touch-icon`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Icon {
+    pub url: String,
+    /// The `rel` keyword the icon was declared with
+    pub rel: String,
+    /// Value of the `sizes` attribute, e.g. `32x32` or `any`
+    pub sizes: Option<String>,
+    /// MIME type from the `type` attribute
+    pub mime_type: Option<String>,
+}
+
+/// A translation of the page in another language or region
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AlternateLink {
+    /// Language or region, or `x-default`
+    pub hreflang: String,
+    pub url: String,
+}
+
+const ICON_RELS: &[&str] = &["icon", "apple-touch-icon", "apple-touch-icon-precomposed"];
+
+/// Collect the page's `<link>` metadata, resolving URLs against `base_uri`
+pub fn extract_links(document: &Html, og_url: Option<&str>, base_uri: Option<&str>) -> ArticleLinks {
+    let resolve = |url: &str| match base_uri {
+        Some(base_uri) => to_absolute_uri(url, base_uri),
+        None => url.to_string(),
+    };
+    let link_selector = Selector::parse("link[rel][href]").unwrap();
+    let mut links = ArticleLinks::default();
+
+    for link in document.select(&link_selector) {
+        let href = match attr(&link, "href") {
+            Some(href) => resolve(href),
+            None => continue,
+        };
+        let rels: Vec<String> = link.value().attr("rel").unwrap_or("")
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        let has_rel = |name: &str| rels.iter().any(|rel| rel == name);
+
+        if has_rel("canonical") && links.canonical.is_none() {
+            links.canonical = Some(href.clone());
+        }
+        if has_rel("amphtml") && links.amp.is_none() {
+            links.amp = Some(href.clone());
+        }
+        if has_rel("alternate") {
+            if let Some(hreflang) = attr(&link, "hreflang") {
+                links.alternates.push(AlternateLink { hreflang: hreflang.to_string(), url: href.clone() });
+            }
+        }
+        if let Some(rel) = rels.iter().find(|rel| ICON_RELS.contains(&rel.as_str())) {
+            links.icons.push(Icon {
+                url: href.clone(),
+                rel: rel.clone(),
+                sizes: attr(&link, "sizes").map(str::to_string),
+                mime_type: attr(&link, "type").map(str::to_string),
+            });
+        }
+    }
+
+    if links.canonical.is_none() {
+        links.canonical = og_url.map(str::trim).filter(|url| !url.is_empty()).map(resolve);
+    }
+
+    links
+}
+
+fn attr<'a>(element: &ElementRef<'a>, name: &str) -> Option<&'a str> {
+    element.value().attr(name).map(str::trim).filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_links() {
+        let document = Html::parse_document(r#"<html><head>
+            <link rel="canonical" href="/news/story">
+            <link rel="amphtml" href="https://amp.example.com/news/story">
+            <link rel="shortcut icon" href="/favicon.ico">
+            <link rel="apple-touch-icon" sizes="180x180" type="image/png" href="/touch.png">
+            <link rel="alternate" hreflang="de" href="/de/news/story">
+            <link rel="alternate" hreflang="x-default" href="/news/story">
+            <link rel="alternate" type="application/rss+xml" href="/feed.xml">
+            <link rel="stylesheet" href="/style.css">
+        </head></html>"#);
+
+        let links = extract_links(&document, Some("https://example.com/og"), Some("https://example.com/news/story?utm=x"));
+        assert_eq!(links.canonical, Some("https://example.com/news/story".to_string()));
+        assert_eq!(links.amp, Some("https://amp.example.com/news/story".to_string()));
+        assert_eq!(links.icons, vec![
+            Icon { url: "https://example.com/favicon.ico".to_string(), rel: "icon".to_string(), sizes: None, mime_type: None },
+            Icon {
+                url: "https://example.com/touch.png".to_string(),
+                rel: "apple-touch-icon".to_string(),
+                sizes: Some("180x180".to_string()),
+                mime_type: Some("image/png".to_string()),
+            },
+        ]);
+        assert_eq!(links.alternates, vec![
+            AlternateLink { hreflang: "de".to_string(), url: "https://example.com/de/news/story".to_string() },
+            AlternateLink { hreflang: "x-default".to_string(), url: "https://example.com/news/story".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn test_canonical_falls_back_to_og_url() {
+        let document = Html::parse_document("<html><head></head></html>");
+        let links = extract_links(&document, Some("/og-url"), Some("https://example.com/page"));
+        assert_eq!(links.canonical, Some("https://example.com/og-url".to_string()));
+
+        let links = extract_links(&document, None, None);
+        assert_eq!(links, ArticleLinks::default());
+    }
+}
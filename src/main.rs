@@ -1,11 +1,14 @@
 //! Command-line interface for the Readability library
 
-use clap::{Arg, Command};
-use readability::{Readability, ReadabilityOptions, is_probably_readerable};
-use serde_json;
+use clap::{error::ErrorKind, parser::ValueSource, Arg, Command, ValueEnum};
+use readability::{
+    Readability, ReadabilityOptions, ReaderableOptions, assess_readerability, write_epub, EpubOptions, SanitizeOptions,
+    Exporter, JsonExporter, TextExporter, HtmlExporter, MarkdownExporter, BlocksExporter, MozillaJsonExporter,
+    BibtexExporter, CslJsonExporter, LatexExporter, AsciiDocExporter, OrgExporter,
+};
 use std::fs;
-use std::io::{self, Read};
-use std::path::Path;
+use std::io::{self, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
 use std::process;
 
 #[derive(Debug)]
@@ -13,34 +16,58 @@ struct CliOptions {
     input: Option<String>,
     output: Option<String>,
     format: OutputFormat,
+    /// Whether `--format` was given rather than defaulted
+    format_given: bool,
     base_uri: Option<String>,
+    wrap_width: usize,
     debug: bool,
     check_only: bool,
+    min_content_length: usize,
+    min_score: f64,
     char_threshold: usize,
     keep_classes: bool,
     disable_json_ld: bool,
+    sanitize: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, ValueEnum)]
 enum OutputFormat {
     Json,
     Text,
     Html,
+    #[value(alias = "md")]
+    Markdown,
+    Blocks,
+    MozillaJson,
+    Bibtex,
+    CslJson,
+    #[value(alias = "tex")]
+    Latex,
+    #[value(name = "asciidoc", alias = "adoc")]
+    AsciiDoc,
+    Org,
 }
 
-impl From<&str> for OutputFormat {
-    fn from(s: &str) -> Self {
-        match s.to_lowercase().as_str() {
-            "json" => OutputFormat::Json,
-            "text" => OutputFormat::Text,
-            "html" => OutputFormat::Html,
-            _ => OutputFormat::Json, // Default
+impl OutputFormat {
+    fn exporter(&self, wrap_width: Option<usize>) -> Box<dyn Exporter> {
+        match self {
+            OutputFormat::Json => Box::new(JsonExporter),
+            OutputFormat::Text => Box::new(TextExporter { wrap_width }),
+            OutputFormat::Html => Box::new(HtmlExporter),
+            OutputFormat::Markdown => Box::new(MarkdownExporter),
+            OutputFormat::Blocks => Box::new(BlocksExporter),
+            OutputFormat::MozillaJson => Box::new(MozillaJsonExporter),
+            OutputFormat::Bibtex => Box::new(BibtexExporter),
+            OutputFormat::CslJson => Box::new(CslJsonExporter),
+            OutputFormat::Latex => Box::new(LatexExporter),
+            OutputFormat::AsciiDoc => Box::new(AsciiDocExporter),
+            OutputFormat::Org => Box::new(OrgExporter),
         }
     }
 }
 
 fn main() {
-    let matches = Command::new("readability")
+    let mut cli = Command::new("readability")
         .version("0.1.0")
         .author("Mozilla Readability Rust Port")
         .about("Extract article content from web pages, removing clutter like ads and navigation")
@@ -65,8 +92,9 @@ fn main() {
                 .short('f')
                 .long("format")
                 .value_name("FORMAT")
-                .help("Output format: json, text, html")
+                .help("Output format")
                 .default_value("json")
+                .value_parser(clap::value_parser!(OutputFormat))
                 .required(false)
         )
         .arg(
@@ -77,6 +105,14 @@ fn main() {
                 .help("Base URI for resolving relative URLs")
                 .required(false)
         )
+        .arg(
+            Arg::new("wrap")
+                .long("wrap")
+                .value_name("WIDTH")
+                .help("Wrap text output at this many characters (0 = no wrapping)")
+                .default_value("0")
+                .value_parser(clap::value_parser!(usize))
+        )
         .arg(
             Arg::new("debug")
                 .short('d')
@@ -88,7 +124,7 @@ fn main() {
             Arg::new("check")
                 .short('c')
                 .long("check")
-                .help("Only check if document is readable (exit code 0=readable, 1=not readable)")
+                .help("Only check if document is readable (exit code 0=readable, 1=not readable; --format json prints a report)")
                 .action(clap::ArgAction::SetTrue)
         )
         .arg(
@@ -99,6 +135,14 @@ fn main() {
                 .default_value("140")
                 .value_parser(clap::value_parser!(usize))
         )
+        .arg(
+            Arg::new("min-score")
+                .long("min-score")
+                .value_name("SCORE")
+                .help("Minimum score for readability check")
+                .default_value("20")
+                .value_parser(clap::value_parser!(f64))
+        )
         .arg(
             Arg::new("char-threshold")
                 .long("char-threshold")
@@ -119,18 +163,106 @@ fn main() {
                 .help("Disable JSON-LD parsing for metadata")
                 .action(clap::ArgAction::SetTrue)
         )
-        .get_matches();
+        .arg(
+            Arg::new("sanitize")
+                .long("sanitize")
+                .help("Sanitize the content against the default tag, attribute and URL scheme allowlist")
+                .action(clap::ArgAction::SetTrue)
+        )
+        .subcommand(
+            Command::new("epub")
+                .about("Bundle the articles of one or more HTML files into an EPUB book")
+                .arg(
+                    Arg::new("inputs")
+                        .value_name("FILE")
+                        .help("Input HTML files, one chapter each")
+                        .num_args(1..)
+                        .required(true)
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .value_name("FILE")
+                        .help("Output EPUB file (default: stdout)")
+                )
+                .arg(
+                    Arg::new("resources")
+                        .short('r')
+                        .long("resources")
+                        .value_name("DIR")
+                        .help("Directory holding the article images, matched by file name")
+                )
+                .arg(
+                    Arg::new("title")
+                        .long("title")
+                        .value_name("TITLE")
+                        .help("Book title (default: the title of a single article)")
+                )
+                .arg(
+                    Arg::new("author")
+                        .long("author")
+                        .value_name("AUTHOR")
+                        .help("Book author (default: the article bylines)")
+                )
+                .arg(
+                    Arg::new("lang")
+                        .long("lang")
+                        .value_name("LANG")
+                        .help("Book language (default: the language of the first article)")
+                )
+                .arg(
+                    Arg::new("base-uri")
+                        .short('b')
+                        .long("base-uri")
+                        .value_name("URI")
+                        .help("Base URI for resolving relative URLs")
+                )
+        );
+    let matches = cli.get_matches_mut();
+
+    if let Some(epub_matches) = matches.subcommand_matches("epub") {
+        let inputs: Vec<String> = epub_matches.get_many::<String>("inputs").unwrap().cloned().collect();
+        let epub_options = EpubOptions {
+            title: epub_matches.get_one::<String>("title").cloned(),
+            author: epub_matches.get_one::<String>("author").cloned(),
+            lang: epub_matches.get_one::<String>("lang").cloned(),
+            resource_dir: epub_matches.get_one::<String>("resources").map(PathBuf::from),
+            ..Default::default()
+        };
+        let output = epub_matches.get_one::<String>("output").cloned();
+        let base_uri = epub_matches.get_one::<String>("base-uri").cloned();
+
+        if let Err(e) = run_epub(&inputs, &epub_options, &output, &base_uri) {
+            eprintln!("Error: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    // The check only has a JSON report
+    let format = matches.get_one::<OutputFormat>("format").unwrap().clone();
+    let format_given = matches.value_source("format") == Some(ValueSource::CommandLine);
+    if matches.get_flag("check") && format_given && !matches!(format, OutputFormat::Json) {
+        let name = format.to_possible_value().unwrap().get_name().to_string();
+        cli.error(ErrorKind::ArgumentConflict, format!("--check only supports --format json, not '{}'", name)).exit();
+    }
 
     let cli_options = CliOptions {
         input: matches.get_one::<String>("input").cloned(),
         output: matches.get_one::<String>("output").cloned(),
-        format: OutputFormat::from(matches.get_one::<String>("format").unwrap().as_str()),
+        format,
+        format_given,
         base_uri: matches.get_one::<String>("base-uri").cloned(),
+        wrap_width: *matches.get_one::<usize>("wrap").unwrap(),
         debug: matches.get_flag("debug"),
         check_only: matches.get_flag("check"),
+        min_content_length: *matches.get_one::<usize>("min-content-length").unwrap(),
+        min_score: *matches.get_one::<f64>("min-score").unwrap(),
         char_threshold: *matches.get_one::<usize>("char-threshold").unwrap(),
         keep_classes: matches.get_flag("keep-classes"),
         disable_json_ld: matches.get_flag("disable-json-ld"),
+        sanitize: matches.get_flag("sanitize"),
     };
 
     if let Err(e) = run(cli_options) {
@@ -149,11 +281,20 @@ fn run(options: CliOptions) -> Result<(), Box<dyn std::error::Error>> {
 
     // If check-only mode, just test readability
     if options.check_only {
-        let readable = is_probably_readerable(&html, None);
+        let readerable_options = ReaderableOptions {
+            min_content_length: options.min_content_length,
+            min_score: options.min_score,
+            ..Default::default()
+        };
+        let assessment = assess_readerability(&html, Some(readerable_options));
         if options.debug {
-            eprintln!("Document is {}readable", if readable { "" } else { "not " });
+            eprintln!("Document is {}readable (score {:.2})", if assessment.readerable { "" } else { "not " }, assessment.score);
+        }
+        // A bare --check stays silent and only sets the exit code
+        if options.format_given {
+            write_output(&serde_json::to_string_pretty(&assessment)?, &options.output)?;
         }
-        process::exit(if readable { 0 } else { 1 });
+        process::exit(if assessment.readerable { 0 } else { 1 });
     }
 
     // Create readability options
@@ -162,6 +303,7 @@ fn run(options: CliOptions) -> Result<(), Box<dyn std::error::Error>> {
         char_threshold: options.char_threshold,
         keep_classes: options.keep_classes,
         disable_json_ld: options.disable_json_ld,
+        sanitize: options.sanitize.then(SanitizeOptions::default),
         ..Default::default()
     };
 
@@ -177,13 +319,15 @@ fn run(options: CliOptions) -> Result<(), Box<dyn std::error::Error>> {
     
     match article {
         Some(article) => {
-            let output = format_output(&article, &options.format)?;
+            let wrap_width = Some(options.wrap_width).filter(|width| *width > 0);
+            let output = format_output(&article, &options.format, wrap_width)?;
             write_output(&output, &options.output)?;
             
             if options.debug {
                 eprintln!("Successfully extracted article:");
                 eprintln!("  Title: {}", article.title.as_deref().unwrap_or("None"));
                 eprintln!("  Length: {} characters", article.length.unwrap_or(0));
+                eprintln!("  Reading time: {} min", article.reading_time_minutes.unwrap_or(0));
             }
         }
         None => {
@@ -195,6 +339,34 @@ fn run(options: CliOptions) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+fn run_epub(
+    inputs: &[String],
+    options: &EpubOptions,
+    output: &Option<String>,
+    base_uri: &Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut articles = Vec::new();
+    for input in inputs {
+        let html = read_input(&Some(input.clone()))?;
+        let mut readability = match base_uri {
+            Some(base_uri) => Readability::new_with_base_uri(&html, base_uri, None)?,
+            None => Readability::new(&html, None)?,
+        };
+        let article = readability.parse()
+            .ok_or_else(|| format!("Failed to extract article content from '{}'", input))?;
+        articles.push(article);
+    }
+
+    let mut buffer = Cursor::new(Vec::new());
+    write_epub(&articles, options, &mut buffer)?;
+
+    match output {
+        Some(path) => fs::write(path, buffer.into_inner())?,
+        None => io::stdout().write_all(&buffer.into_inner())?,
+    }
+    Ok(())
+}
+
 fn read_input(input: &Option<String>) -> Result<String, Box<dyn std::error::Error>> {
     match input {
         Some(path) if path == "-" => {
@@ -234,67 +406,9 @@ fn write_output(content: &str, output: &Option<String>) -> Result<(), Box<dyn st
 fn format_output(
     article: &readability::Article,
     format: &OutputFormat,
+    wrap_width: Option<usize>,
 ) -> Result<String, Box<dyn std::error::Error>> {
-    match format {
-        OutputFormat::Json => {
-            let json = serde_json::to_string_pretty(article)?;
-            Ok(json)
-        }
-        OutputFormat::Text => {
-            let mut output = String::new();
-            
-            if let Some(title) = &article.title {
-                output.push_str(&format!("Title: {}\n\n", title));
-            }
-            
-            if let Some(byline) = &article.byline {
-                output.push_str(&format!("By: {}\n\n", byline));
-            }
-            
-            if let Some(text_content) = &article.text_content {
-                output.push_str(text_content);
-            }
-            
-            Ok(output)
-        }
-        OutputFormat::Html => {
-            let mut output = String::new();
-            output.push_str("<!DOCTYPE html>\n<html>\n<head>\n");
-            
-            if let Some(title) = &article.title {
-                output.push_str(&format!("    <title>{}</title>\n", html_escape(title)));
-            }
-            
-            output.push_str("    <meta charset=\"utf-8\">\n");
-            output.push_str("    <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
-            output.push_str("</head>\n<body>\n");
-            
-            if let Some(title) = &article.title {
-                output.push_str(&format!("    <h1>{}</h1>\n", html_escape(title)));
-            }
-            
-            if let Some(byline) = &article.byline {
-                output.push_str(&format!("    <p class=\"byline\">By {}</p>\n", html_escape(byline)));
-            }
-            
-            if let Some(content) = &article.content {
-                output.push_str("    <div class=\"content\">\n");
-                output.push_str(content);
-                output.push_str("\n    </div>\n");
-            }
-            
-            output.push_str("</body>\n</html>\n");
-            Ok(output)
-        }
-    }
-}
-
-fn html_escape(text: &str) -> String {
-    text.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&#39;")
+    Ok(format.exporter(wrap_width).export(article)?)
 }
 
 #[cfg(test)]
@@ -303,16 +417,18 @@ mod tests {
 
     #[test]
     fn test_output_format_from_str() {
-        assert!(matches!(OutputFormat::from("json"), OutputFormat::Json));
-        assert!(matches!(OutputFormat::from("text"), OutputFormat::Text));
-        assert!(matches!(OutputFormat::from("html"), OutputFormat::Html));
-        assert!(matches!(OutputFormat::from("invalid"), OutputFormat::Json)); // Default
-    }
-
-    #[test]
-    fn test_html_escape() {
-        assert_eq!(html_escape("Hello & <World>"), "Hello &amp; &lt;World&gt;");
-        assert_eq!(html_escape("\"Test\""), "&quot;Test&quot;");
+        assert!(matches!(OutputFormat::from_str("json", true).unwrap(), OutputFormat::Json));
+        assert!(matches!(OutputFormat::from_str("text", true).unwrap(), OutputFormat::Text));
+        assert!(matches!(OutputFormat::from_str("html", true).unwrap(), OutputFormat::Html));
+        assert!(matches!(OutputFormat::from_str("markdown", true).unwrap(), OutputFormat::Markdown));
+        assert!(matches!(OutputFormat::from_str("blocks", true).unwrap(), OutputFormat::Blocks));
+        assert!(matches!(OutputFormat::from_str("mozilla-json", true).unwrap(), OutputFormat::MozillaJson));
+        assert!(matches!(OutputFormat::from_str("bibtex", true).unwrap(), OutputFormat::Bibtex));
+        assert!(matches!(OutputFormat::from_str("csl-json", true).unwrap(), OutputFormat::CslJson));
+        assert!(matches!(OutputFormat::from_str("latex", true).unwrap(), OutputFormat::Latex));
+        assert!(matches!(OutputFormat::from_str("adoc", true).unwrap(), OutputFormat::AsciiDoc));
+        assert!(matches!(OutputFormat::from_str("org", true).unwrap(), OutputFormat::Org));
+        assert!(OutputFormat::from_str("invalid", true).is_err());
     }
 
     #[test]
@@ -324,13 +440,10 @@ mod tests {
             length: Some(12),
             excerpt: None,
             byline: Some("Test Author".to_string()),
-            dir: None,
-            site_name: None,
-            lang: None,
-            published_time: None,
+            ..Default::default()
         };
 
-        let result = format_output(&article, &OutputFormat::Json).unwrap();
+        let result = format_output(&article, &OutputFormat::Json, None).unwrap();
         assert!(result.contains("Test Title"));
         assert!(result.contains("Test content"));
         assert!(result.contains("Test Author"));
@@ -345,16 +458,11 @@ mod tests {
             length: Some(12),
             excerpt: None,
             byline: Some("Test Author".to_string()),
-            dir: None,
-            site_name: None,
-            lang: None,
-            published_time: None,
+            ..Default::default()
         };
 
-        let result = format_output(&article, &OutputFormat::Text).unwrap();
-        assert!(result.contains("Title: Test Title"));
-        assert!(result.contains("By: Test Author"));
-        assert!(result.contains("Test content"));
+        let result = format_output(&article, &OutputFormat::Text, None).unwrap();
+        assert_eq!(result, "Test Title\n==========\n\nBy Test Author\n\nTest content\n");
     }
 
     #[test]
@@ -366,17 +474,45 @@ mod tests {
             length: Some(12),
             excerpt: None,
             byline: Some("Test Author".to_string()),
-            dir: None,
-            site_name: None,
-            lang: None,
-            published_time: None,
+            ..Default::default()
         };
 
-        let result = format_output(&article, &OutputFormat::Html).unwrap();
+        let result = format_output(&article, &OutputFormat::Html, None).unwrap();
         assert!(result.contains("<!DOCTYPE html>"));
         assert!(result.contains("<title>Test Title</title>"));
         assert!(result.contains("<h1>Test Title</h1>"));
         assert!(result.contains("By Test Author"));
         assert!(result.contains("<p>Test content</p>"));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_format_output_markdown() {
+        let article = readability::Article {
+            title: Some("Test Title".to_string()),
+            content: Some("<p>Test <em>content</em></p><ul><li>Item</li></ul>".to_string()),
+            ..Default::default()
+        };
+
+        let result = format_output(&article, &OutputFormat::Markdown, None).unwrap();
+        assert_eq!(result, "# Test Title\n\nTest *content*\n\n- Item\n");
+    }
+
+    #[test]
+    fn test_format_output_citation() {
+        let mut article = readability::Article {
+            title: Some("Test Title".to_string()),
+            ..Default::default()
+        };
+        assert!(format_output(&article, &OutputFormat::Bibtex, None).is_err());
+
+        article.citation = Some(readability::Citation {
+            title: Some("Test Title".to_string()),
+            authors: vec!["Test Author".to_string()],
+            ..Default::default()
+        });
+        let result = format_output(&article, &OutputFormat::Bibtex, None).unwrap();
+        assert!(result.starts_with("@misc{authortest,"));
+        let result = format_output(&article, &OutputFormat::CslJson, None).unwrap();
+        assert!(result.contains("\"family\": \"Author\""));
+    }
+}
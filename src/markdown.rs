@@ -0,0 +1,406 @@
+//! Markdown rendering of the extracted article content
+
+use scraper::{ElementRef, Html, Node};
+use crate::utils::{normalize_whitespace, RENDER_BLOCK_TAGS};
+
+/// Render an HTML fragment as GitHub Flavored Markdown
+pub fn html_to_markdown(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let blocks = render_blocks(&fragment.root_element());
+    let mut markdown = blocks.join("\n\n");
+    if !markdown.is_empty() {
+        markdown.push('\n');
+    }
+    markdown
+}
+
+fn is_block(element: &ElementRef) -> bool {
+    RENDER_BLOCK_TAGS.contains(&element.value().name())
+}
+
+/// Render the children of a container as Markdown blocks, gathering runs of
+/// inline content into paragraphs
+fn render_blocks(container: &ElementRef) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut paragraph = String::new();
+
+    let flush = |paragraph: &mut String, blocks: &mut Vec<String>| {
+        let text = clean_inline(paragraph)
+            .split("  \n")
+            .map(escape_line_start)
+            .collect::<Vec<_>>()
+            .join("  \n");
+        if !text.is_empty() {
+            blocks.push(text);
+        }
+        paragraph.clear();
+    };
+
+    for child in container.children() {
+        match child.value() {
+            Node::Text(text) => paragraph.push_str(&escape_text(text)),
+            Node::Element(_) => {
+                let element = ElementRef::wrap(child).unwrap();
+                if is_block(&element) {
+                    flush(&mut paragraph, &mut blocks);
+                    blocks.extend(render_block(&element));
+                } else {
+                    paragraph.push_str(&render_inline(&element));
+                }
+            }
+            _ => {}
+        }
+    }
+    flush(&mut paragraph, &mut blocks);
+
+    blocks
+}
+
+fn render_block(element: &ElementRef) -> Vec<String> {
+    let tag = element.value().name();
+    match tag {
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+            let text = clean_inline(&render_inline_children(element));
+            if text.is_empty() {
+                return Vec::new();
+            }
+            let level = tag[1..].parse::<usize>().unwrap();
+            vec![format!("{} {}", "#".repeat(level), text)]
+        }
+        "ul" | "ol" => {
+            let list = render_list(element);
+            if list.is_empty() { Vec::new() } else { vec![list] }
+        }
+        "blockquote" => {
+            let quote = render_blocks(element).join("\n\n");
+            if quote.is_empty() {
+                return Vec::new();
+            }
+            vec![quote.lines()
+                .map(|line| if line.is_empty() { ">".to_string() } else { format!("> {}", line) })
+                .collect::<Vec<_>>()
+                .join("\n")]
+        }
+        "pre" => vec![render_code_block(element)],
+        "table" => {
+            let table = render_table(element);
+            if table.is_empty() { Vec::new() } else { vec![table] }
+        }
+        "hr" => vec!["---".to_string()],
+        _ => render_blocks(element),
+    }
+}
+
+fn render_list(list: &ElementRef) -> String {
+    let ordered = list.value().name() == "ol";
+    let mut number = list.value().attr("start")
+        .and_then(|start| start.trim().parse::<usize>().ok())
+        .unwrap_or(1);
+    let mut items = Vec::new();
+
+    for item in list.children().filter_map(ElementRef::wrap) {
+        if item.value().name() != "li" {
+            // Lists nested directly in lists belong to the previous item
+            if matches!(item.value().name(), "ul" | "ol") {
+                if let Some(last) = items.last_mut() {
+                    let nested = render_list(&item);
+                    *last = format!("{}\n{}", last, indent(&nested, 2));
+                }
+            }
+            continue;
+        }
+
+        let marker = if ordered { format!("{}. ", number) } else { "- ".to_string() };
+        number += 1;
+
+        // Nested lists stay tight, other blocks in an item get blank lines
+        let mut content = String::new();
+        for block in render_blocks(&item) {
+            if !content.is_empty() {
+                content.push_str(if block.starts_with("- ") || starts_with_number(&block) { "\n" } else { "\n\n" });
+            }
+            content.push_str(&block);
+        }
+
+        let content = indent(&content, marker.len());
+        items.push(format!("{}{}", marker, content.trim_start()));
+    }
+
+    items.join("\n")
+}
+
+fn render_code_block(pre: &ElementRef) -> String {
+    let code = pre.children()
+        .filter_map(ElementRef::wrap)
+        .find(|child| child.value().name() == "code");
+    let language = [Some(*pre), code].into_iter()
+        .flatten()
+        .flat_map(|element| element.value().classes().collect::<Vec<_>>())
+        .find_map(|class| class.strip_prefix("language-").or_else(|| class.strip_prefix("lang-")))
+        .unwrap_or("");
+
+    let text = pre.text().collect::<String>();
+    let text = text.strip_prefix('\n').unwrap_or(&text).trim_end();
+    let fence = "`".repeat(longest_run(text, '`').max(2) + 1);
+    format!("{}{}\n{}\n{}", fence, language, text, fence)
+}
+
+fn render_table(table: &ElementRef) -> String {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut header_row = false;
+
+    for row in table.descendants().filter_map(ElementRef::wrap).filter(|element| element.value().name() == "tr") {
+        // Skip rows of nested tables
+        let owner = row.ancestors().filter_map(ElementRef::wrap).find(|ancestor| ancestor.value().name() == "table");
+        if owner.map(|owner| owner.id()) != Some(table.id()) {
+            continue;
+        }
+        let cells: Vec<ElementRef> = row.children()
+            .filter_map(ElementRef::wrap)
+            .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+            .collect();
+        if rows.is_empty() {
+            header_row = cells.iter().all(|cell| cell.value().name() == "th");
+        }
+        rows.push(cells.iter()
+            .map(|cell| clean_inline(&render_inline_children(cell)).replace('|', "\\|").replace('\n', " "))
+            .collect());
+    }
+
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+    if !header_row {
+        // GFM tables need a header, so give them an empty one
+        rows.insert(0, vec![String::new(); columns]);
+    }
+
+    let format_row = |row: &Vec<String>| {
+        let cells: Vec<&str> = (0..columns).map(|i| row.get(i).map(String::as_str).unwrap_or("")).collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let mut lines = vec![format_row(&rows[0]), format!("|{}", " --- |".repeat(columns))];
+    lines.extend(rows[1..].iter().map(format_row));
+    lines.join("\n")
+}
+
+fn render_inline_children(element: &ElementRef) -> String {
+    let mut output = String::new();
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => output.push_str(&escape_text(text)),
+            Node::Element(_) => {
+                let child = ElementRef::wrap(child).unwrap();
+                if is_block(&child) {
+                    // Blocks inside inline context, e.g. in table cells
+                    output.push(' ');
+                    output.push_str(&render_inline_children(&child));
+                    output.push(' ');
+                } else {
+                    output.push_str(&render_inline(&child));
+                }
+            }
+            _ => {}
+        }
+    }
+    output
+}
+
+fn render_inline(element: &ElementRef) -> String {
+    match element.value().name() {
+        "strong" | "b" => wrap_inline(&render_inline_children(element), "**"),
+        "em" | "i" | "cite" => wrap_inline(&render_inline_children(element), "*"),
+        "del" | "s" | "strike" => wrap_inline(&render_inline_children(element), "~~"),
+        "code" | "kbd" | "samp" => {
+            let code = normalize_whitespace(&element.text().collect::<String>());
+            if code.is_empty() {
+                return String::new();
+            }
+            let fence = "`".repeat(longest_run(&code, '`') + 1);
+            let padding = if code.starts_with('`') || code.ends_with('`') { " " } else { "" };
+            format!("{}{}{}{}{}", fence, padding, code, padding, fence)
+        }
+        "a" => {
+            let text = clean_inline(&render_inline_children(element));
+            match element.value().attr("href").map(str::trim).filter(|href| !href.is_empty()) {
+                Some(href) => {
+                    let text = if text.is_empty() { escape_text(href) } else { text };
+                    match element.value().attr("title").filter(|title| !title.is_empty()) {
+                        Some(title) => format!("[{}]({} \"{}\")", text, escape_url(href), title.replace('"', "\\\"")),
+                        None => format!("[{}]({})", text, escape_url(href)),
+                    }
+                }
+                None => text,
+            }
+        }
+        "img" => {
+            let src = element.value().attr("src").or_else(|| element.value().attr("data-src"));
+            match src.map(str::trim).filter(|src| !src.is_empty()) {
+                Some(src) => {
+                    let alt = escape_text(&normalize_whitespace(element.value().attr("alt").unwrap_or("")));
+                    format!("![{}]({})", alt, escape_url(src))
+                }
+                None => String::new(),
+            }
+        }
+        "br" => "  \n".to_string(),
+        "script" | "style" | "noscript" | "template" => String::new(),
+        _ => render_inline_children(element),
+    }
+}
+
+/// Wrap inline content in emphasis markers, keeping surrounding spaces
+/// outside so the markers stay valid
+fn wrap_inline(content: &str, marker: &str) -> String {
+    let trimmed = content.trim();
+    if trimmed.is_empty() {
+        return content.to_string();
+    }
+    let leading = if content.starts_with(char::is_whitespace) { " " } else { "" };
+    let trailing = if content.ends_with(char::is_whitespace) { " " } else { "" };
+    format!("{}{}{}{}{}", leading, marker, trimmed, marker, trailing)
+}
+
+/// Collapse whitespace in inline content while keeping hard line breaks
+fn clean_inline(text: &str) -> String {
+    text.split("  \n")
+        .map(normalize_whitespace)
+        .collect::<Vec<_>>()
+        .join("  \n")
+        .trim_end_matches("  \n")
+        .trim()
+        .to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for (i, c) in text.char_indices() {
+        if c == '&' && starts_with_entity(&text[i..]) {
+            escaped.push_str("&amp;");
+            continue;
+        }
+        if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '~') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Whether the text starts with something Markdown decodes as a character
+/// reference, like `&copy;` or `&#42;`
+fn starts_with_entity(text: &str) -> bool {
+    let reference = match text[1..].split_once(';') {
+        Some((reference, _)) => reference,
+        None => return false,
+    };
+    match reference.strip_prefix('#') {
+        Some(number) => match number.strip_prefix(['x', 'X']) {
+            Some(hex) => !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit()),
+            None => !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()),
+        },
+        None => reference.starts_with(|c: char| c.is_ascii_alphabetic())
+            && reference.chars().all(|c| c.is_ascii_alphanumeric()),
+    }
+}
+
+/// Escape what would make a paragraph line a heading, list, quote or rule,
+/// e.g. `# tag` or `2024. A year`
+fn escape_line_start(line: &str) -> String {
+    if line.starts_with(['#', '-', '+', '>', '=']) {
+        return format!("\\{}", line);
+    }
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 && line[digits..].starts_with(['.', ')']) {
+        return format!("{}\\{}", &line[..digits], &line[digits..]);
+    }
+    line.to_string()
+}
+
+fn escape_url(url: &str) -> String {
+    url.replace(' ', "%20").replace('(', "%28").replace(')', "%29")
+}
+
+fn indent(text: &str, width: usize) -> String {
+    let padding = " ".repeat(width);
+    text.lines()
+        .map(|line| if line.is_empty() { String::new() } else { format!("{}{}", padding, line) })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn starts_with_number(text: &str) -> bool {
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    digits > 0 && text[digits..].starts_with(". ")
+}
+
+fn longest_run(text: &str, c: char) -> usize {
+    text.split(|ch| ch != c).map(str::len).max().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_formatting() {
+        assert_eq!(
+            html_to_markdown(r#"<p>Some <strong>bold </strong>and <em>italic</em> text with <code>a `tick`</code>,
+                a <a href="https://example.com/a b" title="Title">link</a> and <img src="/i.png" alt="An image">.</p>"#),
+            "Some **bold** and *italic* text with `` a `tick` ``, a [link](https://example.com/a%20b \"Title\") and ![An image](/i.png).\n"
+        );
+        assert_eq!(html_to_markdown("<p>2 * 3 = 6_</p><div>Line<br>break</div>"), "2 \\* 3 = 6\\_\n\nLine  \nbreak\n");
+    }
+
+    #[test]
+    fn test_line_start_escaping() {
+        assert_eq!(
+            html_to_markdown("<p># not a heading</p><p>2024. A year</p><p>- dash</p><p>> quote</p>"),
+            "\\# not a heading\n\n2024\\. A year\n\n\\- dash\n\n\\> quote\n"
+        );
+        assert_eq!(
+            html_to_markdown("<p>Total<br>= 3<br>+ 1<br>2) two</p><p>A # in 2024. the middle</p>"),
+            "Total  \n\\= 3  \n\\+ 1  \n2\\) two\n\nA # in 2024. the middle\n"
+        );
+        assert_eq!(html_to_markdown("<ul><li>- item</li></ul>"), "- \\- item\n");
+    }
+
+    #[test]
+    fn test_entity_escaping() {
+        assert_eq!(
+            html_to_markdown("<p>Write &amp;copy; or &amp;#42; or &amp;#x2A; for symbols, but R&amp;D &amp; a&amp;b; stay</p>"),
+            "Write &amp;copy; or &amp;#42; or &amp;#x2A; for symbols, but R&D & a&amp;b; stay\n"
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        let html = r#"<h2>Heading</h2><p>Intro</p>
+            <blockquote><p>Quoted</p><p>Twice</p></blockquote>
+            <pre><code class="language-rust">fn main() {
+    println!("hi");
+}</code></pre><hr>"#;
+        assert_eq!(html_to_markdown(html), "## Heading\n\nIntro\n\n> Quoted\n>\n> Twice\n\n```rust\nfn main() {\n    println!(\"hi\");\n}\n```\n\n---\n");
+    }
+
+    #[test]
+    fn test_nested_lists() {
+        let html = r#"<ul><li>One<ul><li>One A</li><li>One B</li></ul></li><li><p>Two</p><p>More</p></li></ul>
+            <ol start="3"><li>Three</li><li>Four<ol><li>Four A</li></ol></li></ol>"#;
+        assert_eq!(
+            html_to_markdown(html),
+            "- One\n  - One A\n  - One B\n- Two\n\n  More\n\n3. Three\n4. Four\n   1. Four A\n"
+        );
+    }
+
+    #[test]
+    fn test_tables() {
+        let html = r#"<table><thead><tr><th>Name</th><th>Value</th></tr></thead>
+            <tbody><tr><td>a|b</td><td><em>1</em></td></tr><tr><td>c</td></tr></tbody></table>"#;
+        assert_eq!(html_to_markdown(html), "| Name | Value |\n| --- | --- |\n| a\\|b | *1* |\n| c |  |\n");
+
+        let html = "<table><tr><td>x</td><td>y</td></tr></table>";
+        assert_eq!(html_to_markdown(html), "|  |  |\n| --- | --- |\n| x | y |\n");
+    }
+}
@@ -0,0 +1,166 @@
+//! Microdata and RDFa metadata extraction for the Readability parser
+//!
+//! Articles marked up with `itemscope`/`itemprop` or RDFa `typeof`/`property`
+//! are read into the same shape as a JSON-LD object, so the rest of the
+//! parser can treat all three the same way.
+
+use scraper::{ElementRef, Html, Selector};
+use serde_json::{Map, Value};
+use crate::json_ld;
+use crate::utils::normalize_whitespace;
+
+/// Attributes that mark items and their properties in one syntax
+struct Syntax {
+    /// Attribute that starts a new item
+    scope: &'static str,
+    /// Attribute holding the item type
+    item_type: &'static str,
+    /// Attribute holding property names
+    property: &'static str,
+}
+
+const MICRODATA: Syntax = Syntax { scope: "itemscope", item_type: "itemtype", property: "itemprop" };
+const RDFA: Syntax = Syntax { scope: "typeof", item_type: "typeof", property: "property" };
+
+/// Find the first schema.org article item in microdata, then in RDFa
+pub fn find_article_object(document: &Html) -> Option<Value> {
+    for syntax in [&MICRODATA, &RDFA] {
+        let scope_selector = Selector::parse(&format!("[{}]", syntax.scope)).unwrap();
+        for element in document.select(&scope_selector) {
+            let item = read_item(&element, syntax);
+            if json_ld::is_article_object(&item) {
+                return Some(item);
+            }
+        }
+    }
+    None
+}
+
+/// Read an item and its properties into a JSON-LD style object
+fn read_item(element: &ElementRef, syntax: &Syntax) -> Value {
+    let mut item = Map::new();
+
+    let types: Vec<Value> = element.value().attr(syntax.item_type).unwrap_or("")
+        .split_whitespace()
+        .map(|item_type| Value::String(short_name(item_type).to_string()))
+        .collect();
+    match types.len() {
+        0 => {}
+        1 => { item.insert("@type".to_string(), types[0].clone()); }
+        _ => { item.insert("@type".to_string(), Value::Array(types)); }
+    }
+
+    collect_properties(element, syntax, &mut item);
+    Value::Object(item)
+}
+
+fn collect_properties(element: &ElementRef, syntax: &Syntax, item: &mut Map<String, Value>) {
+    for child in element.children().filter_map(ElementRef::wrap) {
+        let starts_item = child.value().attr(syntax.scope).is_some();
+
+        if let Some(names) = child.value().attr(syntax.property) {
+            let value = if starts_item { read_item(&child, syntax) } else { property_value(&child) };
+            for name in names.split_whitespace().map(short_name) {
+                add_property(item, name, value.clone());
+            }
+        }
+
+        // Properties of nested items belong to those items
+        if !starts_item {
+            collect_properties(&child, syntax, item);
+        }
+    }
+}
+
+/// The value of a property element, following the microdata rules
+fn property_value(element: &ElementRef) -> Value {
+    let attr = |name: &str| element.value().attr(name).map(|value| value.trim().to_string());
+
+    let value = attr("content").or_else(|| match element.value().name() {
+        "audio" | "embed" | "iframe" | "img" | "source" | "track" | "video" => attr("src"),
+        "a" | "area" | "link" => attr("href"),
+        "object" => attr("data"),
+        "data" | "meter" => attr("value"),
+        "time" => attr("datetime"),
+        _ => None,
+    });
+
+    Value::String(value.unwrap_or_else(|| {
+        normalize_whitespace(&element.text().collect::<Vec<_>>().join(" "))
+    }))
+}
+
+fn add_property(item: &mut Map<String, Value>, name: &str, value: Value) {
+    match item.get_mut(name) {
+        None => { item.insert(name.to_string(), value); }
+        Some(Value::Array(values)) => values.push(value),
+        Some(existing) => {
+            let first = existing.take();
+            *existing = Value::Array(vec![first, value]);
+        }
+    }
+}
+
+/// `https://schema.org/NewsArticle` and `schema:NewsArticle` are both `NewsArticle`
+fn short_name(name: &str) -> &str {
+    let name = name.trim_end_matches('/');
+    name.rsplit(['/', '#', ':']).next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_microdata_article() {
+        let document = Html::parse_document(r#"<html><body>
+            <div itemscope itemtype="https://schema.org/WebPage"><span itemprop="name">Page</span></div>
+            <article itemscope itemtype="https://schema.org/NewsArticle">
+                <h1 itemprop="headline">Microdata   Headline</h1>
+                <span itemprop="author" itemscope itemtype="https://schema.org/Person">
+                    <a itemprop="url" href="https://example.com/jane"><span itemprop="name">Jane Doe</span></a>
+                </span>
+                <span itemprop="author" itemscope itemtype="https://schema.org/Person"><span itemprop="name">John Roe</span></span>
+                <time itemprop="datePublished dateCreated" datetime="2021-05-05T10:00:00Z">May 5</time>
+                <meta itemprop="inLanguage" content="en">
+                <div itemprop="articleBody"><p>Body text.</p></div>
+            </article>
+        </body></html>"#);
+
+        let article = find_article_object(&document).unwrap();
+        assert_eq!(article["@type"], "NewsArticle");
+        assert_eq!(article["headline"], "Microdata Headline");
+        assert_eq!(article["author"][0]["name"], "Jane Doe");
+        assert_eq!(article["author"][0]["url"], "https://example.com/jane");
+        assert_eq!(article["author"][1]["name"], "John Roe");
+        assert_eq!(article["datePublished"], "2021-05-05T10:00:00Z");
+        assert_eq!(article["dateCreated"], "2021-05-05T10:00:00Z");
+        assert_eq!(article["inLanguage"], "en");
+        assert_eq!(article["articleBody"], "Body text.");
+    }
+
+    #[test]
+    fn test_rdfa_article() {
+        let document = Html::parse_document(r#"<html><body>
+            <div vocab="https://schema.org/" typeof="BlogPosting">
+                <h1 property="schema:headline">RDFa Headline</h1>
+                <div property="author" typeof="Person"><span property="name">Jane Doe</span></div>
+                <span property="datePublished" content="2020-01-02">January 2nd</span>
+            </div>
+        </body></html>"#);
+
+        let article = find_article_object(&document).unwrap();
+        assert_eq!(article["@type"], "BlogPosting");
+        assert_eq!(article["headline"], "RDFa Headline");
+        assert_eq!(article["author"]["name"], "Jane Doe");
+        assert_eq!(article["datePublished"], "2020-01-02");
+    }
+
+    #[test]
+    fn test_no_article_item() {
+        let document = Html::parse_document(r#"<html><body>
+            <div itemscope itemtype="https://schema.org/Product"><span itemprop="name">Thing</span></div>
+        </body></html>"#);
+        assert!(find_article_object(&document).is_none());
+    }
+}
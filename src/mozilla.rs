@@ -0,0 +1,100 @@
+//! The article in the JSON shape of Readability.js, for drop-in compatibility
+
+use serde::{Deserialize, Serialize};
+use crate::Article;
+
+/// An `Article` with the field names of the object Readability.js `parse()`
+/// returns. `dir` and `lang` are the raw attributes Readability.js reads,
+/// without the inferred direction or the language fallbacks of `Article`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MozillaArticle {
+    /// Empty when the page has no title
+    pub title: String,
+    pub byline: Option<String>,
+    pub dir: Option<String>,
+    pub lang: Option<String>,
+    pub content: String,
+    pub text_content: String,
+    /// Length of `text_content` in UTF-16 code units, like `String.length`
+    pub length: usize,
+    /// Left out, rather than null, when there is no excerpt, as
+    /// `JSON.stringify` drops the undefined value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub excerpt: Option<String>,
+    pub site_name: Option<String>,
+    pub published_time: Option<String>,
+}
+
+impl From<&Article> for MozillaArticle {
+    fn from(article: &Article) -> Self {
+        let text_content = article.text_content.clone().unwrap_or_default();
+        Self {
+            title: article.title.clone().unwrap_or_default(),
+            byline: article.byline.clone(),
+            dir: article.dir_attribute.clone(),
+            lang: article.html_lang.clone(),
+            content: article.content.clone().unwrap_or_default(),
+            length: text_content.encode_utf16().count(),
+            text_content,
+            excerpt: article.excerpt.clone(),
+            site_name: article.site_name.clone(),
+            published_time: article.published_time.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Readability;
+
+    #[test]
+    fn test_mozilla_shape() {
+        let article = Article {
+            title: Some("Title".to_string()),
+            content: Some("<p>𝔘nicode text</p>".to_string()),
+            text_content: Some("𝔘nicode text".to_string()),
+            length: Some(12),
+            site_name: Some("Example".to_string()),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_value(MozillaArticle::from(&article)).unwrap();
+        assert_eq!(json, serde_json::json!({
+            "title": "Title",
+            "byline": null,
+            "dir": null,
+            "lang": null,
+            "content": "<p>𝔘nicode text</p>",
+            "textContent": "𝔘nicode text",
+            "length": 13,
+            "siteName": "Example",
+            "publishedTime": null,
+        }));
+
+        let article = Article { excerpt: Some("Summary".to_string()), ..Default::default() };
+        let json = serde_json::to_value(MozillaArticle::from(&article)).unwrap();
+        assert_eq!(json["title"], "");
+        assert_eq!(json["excerpt"], "Summary");
+    }
+
+    #[test]
+    fn test_raw_dir_and_lang() {
+        let paragraph = "تعلن الحكومة عن خطة جديدة لتطوير التعليم في جميع المدارس خلال السنوات القادمة. ".repeat(12);
+        let html = format!(r#"<html><head><meta property="og:locale" content="ar_AR"></head>
+            <body><article><p>{}</p></article></body></html>"#, paragraph);
+        let article = Readability::new(&html, None).unwrap().parse().unwrap();
+        assert_eq!(article.dir.as_deref(), Some("rtl"));
+        assert_eq!(article.lang.as_deref(), Some("ar-AR"));
+        let mozilla = article.to_mozilla();
+        assert_eq!(mozilla.dir, None);
+        assert_eq!(mozilla.lang, None);
+
+        let html = format!(r#"<html lang=" EN_us" dir="LTR"><body>
+            <div dir="RTL"><article><p>{}</p></article></div></body></html>"#, paragraph);
+        let mozilla = Readability::new(&html, None).unwrap().parse().unwrap().to_mozilla();
+        assert_eq!(mozilla.dir.as_deref(), Some("RTL"));
+        assert_eq!(mozilla.lang.as_deref(), Some(" EN_us"));
+    }
+}
@@ -0,0 +1,241 @@
+//! Org-mode rendering of the article block tree
+
+use crate::blocks::{render_spans, single_line, Block, EmbedKind, Mark, Span, TableRow};
+
+const ZERO_WIDTH_SPACE: char = '\u{200B}';
+
+/// Render blocks as the body of an Org document
+pub fn blocks_to_org(blocks: &[Block]) -> String {
+    let mut org = render_blocks(blocks).join("\n\n");
+    if !org.is_empty() {
+        org.push('\n');
+    }
+    org
+}
+
+fn render_blocks(blocks: &[Block]) -> Vec<String> {
+    blocks.iter()
+        .map(render_block)
+        .filter(|block| !block.is_empty())
+        .collect()
+}
+
+fn render_block(block: &Block) -> String {
+    match block {
+        Block::Heading { level, spans } => {
+            format!("{} {}", "*".repeat(*level as usize), render_inline(&single_line(spans)))
+        }
+        Block::Paragraph { spans } => render_inline(spans),
+        Block::List { ordered, start, items } => render_list(*ordered, *start, items),
+        Block::Quote { blocks } => format!("#+BEGIN_QUOTE\n{}\n#+END_QUOTE", render_blocks(blocks).join("\n\n")),
+        Block::Code { lang, text } => {
+            // Lines Org would read as headlines or keywords are escaped with a comma
+            let text = text.lines()
+                .map(|line| {
+                    let bare = line.trim_start_matches(',');
+                    if bare.starts_with('*') || bare.starts_with("#+") { format!(",{}", line) } else { line.to_string() }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            match lang {
+                Some(lang) => format!("#+BEGIN_SRC {}\n{}\n#+END_SRC", lang, text),
+                None => format!("#+BEGIN_EXAMPLE\n{}\n#+END_EXAMPLE", text),
+            }
+        }
+        Block::Image { src, alt, caption, link } => {
+            let mut lines = Vec::new();
+            if let Some(caption) = caption {
+                lines.push(format!("#+CAPTION: {}", caption));
+            }
+            if let Some(alt) = alt.as_deref().filter(|alt| !alt.is_empty()) {
+                lines.push(format!("#+ATTR_HTML: :alt {}", alt));
+            }
+            // An image as the description of a link is shown inline
+            match link {
+                Some(link) => lines.push(format!("[[{}][{}]]", escape_url(link), escape_url(src))),
+                None => lines.push(format!("[[{}]]", escape_url(src))),
+            }
+            lines.join("\n")
+        }
+        Block::Table { rows } => render_table(rows),
+        Block::Embed { kind, src } => {
+            let label = match kind {
+                EmbedKind::Video => "Video",
+                EmbedKind::Audio => "Audio",
+                EmbedKind::Iframe | EmbedKind::Object => "Embedded content",
+            };
+            format!("[[{}][{}]]", escape_url(src), label)
+        }
+        Block::Rule => "-----".to_string(),
+    }
+}
+
+fn render_list(ordered: bool, start: Option<u32>, items: &[Vec<Block>]) -> String {
+    let mut number = start.unwrap_or(1);
+    let mut rendered_items = Vec::new();
+
+    for (i, item) in items.iter().enumerate() {
+        let mut marker = if ordered { format!("{}. ", number) } else { "- ".to_string() };
+        number += 1;
+        let padding = " ".repeat(marker.len());
+        // A counter cookie makes Org start numbering at the list start
+        if ordered && i == 0 && number - 1 != 1 {
+            marker.push_str(&format!("[@{}] ", number - 1));
+        }
+
+        let mut content = String::new();
+        for block in item {
+            let rendered = render_block(block);
+            if rendered.is_empty() {
+                continue;
+            }
+            if !content.is_empty() {
+                content.push_str(if matches!(block, Block::List { .. }) { "\n" } else { "\n\n" });
+            }
+            content.push_str(&rendered);
+        }
+
+        let lines: Vec<String> = content.lines()
+            .enumerate()
+            .map(|(i, line)| match (i, line.is_empty()) {
+                (0, _) => format!("{}{}", marker, line),
+                (_, true) => String::new(),
+                _ => format!("{}{}", padding, line),
+            })
+            .collect();
+        rendered_items.push(if lines.is_empty() { marker.trim_end().to_string() } else { lines.join("\n") });
+    }
+
+    rendered_items.join("\n")
+}
+
+fn render_table(rows: &[TableRow]) -> String {
+    let columns = rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+
+    let mut lines = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        let mut cells: Vec<String> = row.cells.iter()
+            .map(|cell| render_inline(&single_line(cell)).replace('|', "\\vert{}"))
+            .collect();
+        cells.resize(columns, String::new());
+        lines.push(format!("| {} |", cells.join(" | ")));
+        // Rule under the header rows
+        if row.header && rows.get(i + 1).is_some_and(|next| !next.header) {
+            lines.push(format!("|{}|", vec!["---"; columns].join("+")));
+        }
+    }
+    lines.join("\n")
+}
+
+fn render_inline(spans: &[Span]) -> String {
+    let rendered = render_spans(
+        spans,
+        escape_text,
+        |mark, text| match mark {
+            Mark::Bold => format!("*{}*", text),
+            Mark::Italic => format!("/{}/", text),
+            Mark::Underline => format!("_{}_", text),
+            Mark::Strikethrough => format!("+{}+", text),
+            Mark::Code => format!("~{}~", text),
+            Mark::Superscript => format!("^{{{}}}", text),
+            Mark::Subscript => format!("_{{{}}}", text),
+        },
+        |target, text| {
+            // Link descriptions cannot hold brackets
+            let text = text.replace('[', "(").replace(']', ")");
+            format!("[[{}][{}]]", escape_url(target), text)
+        },
+    );
+
+    // Lines Org would read as keywords, comments, lists or tables are
+    // started with a zero-width space
+    rendered.split('\n')
+        .map(|line| {
+            let digits = line.chars().take_while(char::is_ascii_digit).count();
+            if line.starts_with(['#', '-', '|', ':']) || (digits > 0 && line[digits..].starts_with(['.', ')'])) {
+                format!("{}{}", ZERO_WIDTH_SPACE, line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Keep emphasis markers and link brackets in text from being read as
+/// markup by surrounding them with zero-width spaces, and turn line breaks
+/// into hard breaks
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '*' | '/' | '_' | '+' | '~' | '=' => {
+                escaped.push(ZERO_WIDTH_SPACE);
+                escaped.push(c);
+                escaped.push(ZERO_WIDTH_SPACE);
+            }
+            '[' if escaped.ends_with('[') => {
+                escaped.push(ZERO_WIDTH_SPACE);
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("\\\\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_url(url: &str) -> String {
+    url.replace(' ', "%20").replace('[', "%5B").replace(']', "%5D")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::html_to_blocks;
+
+    #[test]
+    fn test_org_inline() {
+        let blocks = html_to_blocks(r#"<h2>Intro to <em>Org</em></h2>
+            <p>Some <strong>bold </strong>and <code>code</code>, see <a href="https://example.com/a b">the [docs]</a><br>next</p>"#);
+        assert_eq!(
+            blocks_to_org(&blocks),
+            "** Intro to /Org/\n\nSome *bold* and ~code~, see [[https://example.com/a%20b][the (docs)]]\\\\\nnext\n"
+        );
+    }
+
+    #[test]
+    fn test_org_blocks() {
+        let blocks = html_to_blocks(r#"<ol start="3"><li>Three<ul><li>Nested</li></ul></li><li><p>Four</p><p>More</p></li></ol>
+            <blockquote><p>Quoted</p></blockquote><pre><code class="language-md">* item</code></pre>
+            <table><tr><th>A</th><th>B</th></tr><tr><td>1|2</td></tr></table><hr>"#);
+        assert_eq!(
+            blocks_to_org(&blocks),
+            "3. [@3] Three\n   - Nested\n4. Four\n\n   More\n\n\
+             #+BEGIN_QUOTE\nQuoted\n#+END_QUOTE\n\n#+BEGIN_SRC md\n,* item\n#+END_SRC\n\n\
+             | A | B |\n|---+---|\n| 1\\vert{}2 |  |\n\n-----\n"
+        );
+    }
+
+    #[test]
+    fn test_org_linked_image() {
+        let blocks = html_to_blocks(r#"<a href="https://example.com/big.jpg"><img src="small.jpg" alt="Small"></a>"#);
+        assert_eq!(blocks_to_org(&blocks), "#+ATTR_HTML: :alt Small\n[[https://example.com/big.jpg][small.jpg]]\n");
+    }
+
+    #[test]
+    fn test_org_escaping() {
+        let blocks = html_to_blocks("<p>* not a heading</p><p>#+TITLE: x<br>- dash<br>2024. A year</p>\
+            <p>a*b*c, /path/, snake_case, C++, ~x, a=b and [[link]]</p>");
+        assert_eq!(
+            blocks_to_org(&blocks).replace('\u{200B}', "<zwsp>"),
+            "<zwsp>*<zwsp> not a heading\n\n\
+             <zwsp>#<zwsp>+<zwsp>TITLE: x\\\\\n<zwsp>- dash\\\\\n<zwsp>2024. A year\n\n\
+             a<zwsp>*<zwsp>b<zwsp>*<zwsp>c, <zwsp>/<zwsp>path<zwsp>/<zwsp>, snake<zwsp>_<zwsp>case, \
+             C<zwsp>+<zwsp><zwsp>+<zwsp>, <zwsp>~<zwsp>x, a<zwsp>=<zwsp>b and [<zwsp>[link]]\n"
+        );
+    }
+}
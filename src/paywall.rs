@@ -0,0 +1,229 @@
+//! Paywall detection for the Readability parser
+
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::json_ld;
+use crate::regexps::get_regexps;
+
+/// Paywalled sections holding less text than this were not served to us
+const PAYWALLED_SECTION_MIN_LENGTH: usize = 100;
+
+/// Truncation notices are looked for at the end of the content only
+const MARKER_SEARCH_CHARS: usize = 400;
+
+/// What the page tells us about restricted access to its content
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Paywall {
+    /// Whether the extracted content is likely only part of the article
+    pub is_partial: bool,
+    /// `isAccessibleForFree` as declared in the structured data
+    pub accessible_for_free: Option<bool>,
+    /// CSS selectors of the paywalled sections
+    pub selectors: Vec<String>,
+    /// The signals the paywall was detected from
+    pub signals: Vec<PaywallSignal>,
+}
+
+/// A hint that the page is paywalled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaywallSignal {
+    /// `isAccessibleForFree: false` in JSON-LD, microdata or RDFa
+    StructuredData,
+    /// An element with a paywall class or id, such as `.paywall`
+    Container,
+    /// A notice like "Subscribe to continue reading" at the end of the content
+    TruncationMarker,
+}
+
+/// Look for paywall signals in the document, its structured data article and
+/// the extracted text. Returns `None` when the page says nothing about access.
+pub fn detect_paywall(document: &Html, article: Option<&Value>, text_content: &str) -> Option<Paywall> {
+    let mut paywall = Paywall::default();
+
+    if let Some(article) = article {
+        paywall.accessible_for_free = article.get("isAccessibleForFree").and_then(parse_bool);
+        if paywall.accessible_for_free == Some(false) {
+            paywall.signals.push(PaywallSignal::StructuredData);
+            paywall.selectors = paywalled_selectors(article);
+
+            // Publishers often serve the paywalled sections to crawlers, so
+            // the content is only partial if they are missing or empty
+            paywall.is_partial = paywall.selectors.is_empty()
+                || paywall.selectors.iter().any(|selector| !has_section_text(document, selector));
+        }
+    }
+
+    // Sections the structured data declares were already checked for text
+    let containers: Vec<String> = find_paywall_containers(document).into_iter()
+        .filter(|selector| !paywall.selectors.contains(selector))
+        .collect();
+    if !containers.is_empty() {
+        paywall.signals.push(PaywallSignal::Container);
+        paywall.is_partial = true;
+        paywall.selectors.extend(containers);
+    }
+
+    if has_truncation_marker(text_content) {
+        paywall.signals.push(PaywallSignal::TruncationMarker);
+        paywall.is_partial = true;
+    }
+
+    if paywall.signals.is_empty() && paywall.accessible_for_free.is_none() {
+        None
+    } else {
+        Some(paywall)
+    }
+}
+
+/// schema.org booleans are `false`, `"False"` or `"http://schema.org/False"`
+fn parse_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(value) => Some(*value),
+        Value::String(value) => match value.trim().rsplit('/').next()?.to_lowercase().as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// `cssSelector` of every `hasPart` that is not accessible for free
+fn paywalled_selectors(article: &Value) -> Vec<String> {
+    let parts = match article.get("hasPart") {
+        Some(Value::Array(parts)) => parts.iter().collect(),
+        Some(part) => vec![part],
+        None => Vec::new(),
+    };
+
+    parts.into_iter()
+        .filter(|part| part.get("isAccessibleForFree").and_then(parse_bool) == Some(false))
+        .filter_map(|part| json_ld::get_string(part, "cssSelector"))
+        .collect()
+}
+
+fn has_section_text(document: &Html, selector: &str) -> bool {
+    let selector = match Selector::parse(selector) {
+        Ok(selector) => selector,
+        // We cannot check a selector we do not understand
+        Err(_) => return true,
+    };
+    let text_length: usize = document.select(&selector)
+        .map(|section| section.text().map(|text| text.trim().chars().count()).sum::<usize>())
+        .sum();
+    text_length >= PAYWALLED_SECTION_MIN_LENGTH
+}
+
+/// Selectors for the elements whose class or id marks them as a paywall
+fn find_paywall_containers(document: &Html) -> Vec<String> {
+    let regexps = get_regexps();
+    let candidate_selector = Selector::parse("body [class], body [id]").unwrap();
+    let mut selectors = Vec::new();
+
+    for element in document.select(&candidate_selector) {
+        let selector = match container_selector(&element, &regexps.paywall_container) {
+            Some(selector) => selector,
+            None => continue,
+        };
+        // Only the outermost element of a paywall is reported
+        let inside_container = element.ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(|ancestor| container_selector(&ancestor, &regexps.paywall_container).is_some());
+        if !inside_container && !selectors.contains(&selector) {
+            selectors.push(selector);
+        }
+    }
+
+    selectors
+}
+
+fn container_selector(element: &ElementRef, pattern: &regex::Regex) -> Option<String> {
+    if let Some(id) = element.value().id().filter(|id| pattern.is_match(id)) {
+        return Some(format!("#{}", id));
+    }
+    element.value().classes()
+        .find(|class| pattern.is_match(class))
+        .map(|class| format!(".{}", class))
+}
+
+fn has_truncation_marker(text_content: &str) -> bool {
+    let char_count = text_content.chars().count();
+    let tail: String = text_content.chars()
+        .skip(char_count.saturating_sub(MARKER_SEARCH_CHARS))
+        .collect();
+    get_regexps().paywall_marker.is_match(&tail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structured_data_paywall() {
+        let article: Value = serde_json::from_str(r#"{"@type": "NewsArticle", "isAccessibleForFree": "False",
+            "hasPart": {"@type": "WebPageElement", "isAccessibleForFree": false, "cssSelector": ".premium"}}"#).unwrap();
+
+        // The paywalled section is missing from the page
+        let document = Html::parse_document(r#"<html><body><article><p>Teaser.</p></article></body></html>"#);
+        let paywall = detect_paywall(&document, Some(&article), "Teaser.").unwrap();
+        assert_eq!(paywall, Paywall {
+            is_partial: true,
+            accessible_for_free: Some(false),
+            selectors: vec![".premium".to_string()],
+            signals: vec![PaywallSignal::StructuredData],
+        });
+
+        // The paywalled section was served in full
+        let html = format!(r#"<html><body><article><p>Teaser.</p><section class="premium"><p>{}</p></section></article></body></html>"#,
+            "Full text. ".repeat(20));
+        let document = Html::parse_document(&html);
+        let paywall = detect_paywall(&document, Some(&article), "Teaser.").unwrap();
+        assert!(!paywall.is_partial);
+        assert_eq!(paywall.signals, vec![PaywallSignal::StructuredData]);
+    }
+
+    #[test]
+    fn test_structured_data_paywall_container() {
+        // Google's documented markup, with the section named like a paywall container
+        let article: Value = serde_json::from_str(r#"{"@type": "NewsArticle", "isAccessibleForFree": false,
+            "hasPart": {"@type": "WebPageElement", "isAccessibleForFree": false, "cssSelector": ".paywall"}}"#).unwrap();
+
+        let html = format!(r#"<html><body><article><p>Teaser.</p><div class="paywall"><p>{}</p></div></article></body></html>"#,
+            "Full text. ".repeat(20));
+        let paywall = detect_paywall(&Html::parse_document(&html), Some(&article), "Teaser.").unwrap();
+        assert!(!paywall.is_partial);
+        assert_eq!(paywall.selectors, vec![".paywall"]);
+        assert_eq!(paywall.signals, vec![PaywallSignal::StructuredData]);
+
+        let html = r#"<html><body><article><p>Teaser.</p><div class="paywall"></div></article></body></html>"#;
+        let paywall = detect_paywall(&Html::parse_document(html), Some(&article), "Teaser.").unwrap();
+        assert!(paywall.is_partial);
+        assert_eq!(paywall.signals, vec![PaywallSignal::StructuredData]);
+    }
+
+    #[test]
+    fn test_paywall_container_and_marker() {
+        let document = Html::parse_document(r#"<html><body><article><p>Teaser.</p>
+            <div class="article-footer piano-offer"><div class="paywall-message">Subscribe</div></div>
+            </article></body></html>"#);
+        let paywall = detect_paywall(&document, None, "Teaser. Subscribe now to continue reading.").unwrap();
+        assert!(paywall.is_partial);
+        assert_eq!(paywall.accessible_for_free, None);
+        assert_eq!(paywall.selectors, vec![".piano-offer"]);
+        assert_eq!(paywall.signals, vec![PaywallSignal::Container, PaywallSignal::TruncationMarker]);
+    }
+
+    #[test]
+    fn test_free_article() {
+        let document = Html::parse_document(r#"<html><body><article><p>Free to read.</p></article></body></html>"#);
+        assert_eq!(detect_paywall(&document, None, "Free to read."), None);
+
+        let article: Value = serde_json::from_str(r#"{"@type": "Article", "isAccessibleForFree": true}"#).unwrap();
+        let paywall = detect_paywall(&document, Some(&article), "Free to read.").unwrap();
+        assert!(!paywall.is_partial);
+        assert_eq!(paywall.accessible_for_free, Some(true));
+        assert!(paywall.signals.is_empty());
+    }
+}
@@ -0,0 +1,371 @@
+//! Quick check of whether a document probably contains an article, without
+//! running the full parser
+
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use crate::regexps::is_unlikely_candidate;
+use crate::utils::is_node_visible;
+
+/// Options for `is_probably_readerable`
+#[derive(Debug, Clone)]
+pub struct ReaderableOptions {
+    /// Minimum length of a node's text for it to count towards the score
+    pub min_content_length: usize,
+    /// Score the document must exceed to be considered readerable
+    pub min_score: f64,
+    /// Decides whether a node is visible; hidden nodes are ignored
+    pub visibility_checker: fn(&ElementRef) -> bool,
+}
+
+impl Default for ReaderableOptions {
+    fn default() -> Self {
+        Self {
+            min_content_length: 140,
+            min_score: 20.0,
+            visibility_checker: is_node_visible,
+        }
+    }
+}
+
+/// Detailed result of `assess_readerability`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReaderableAssessment {
+    /// Same answer as `is_probably_readerable`
+    pub readerable: bool,
+    /// Sum of the node scores, for ranking pages against each other
+    pub score: f64,
+    /// The nodes that added to the score, in document order
+    pub contributing_nodes: Vec<ReaderableNode>,
+    /// Why the page is not readerable; empty when it is
+    pub failure_reasons: Vec<ReaderableFailure>,
+}
+
+/// A node that added to the readerable score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReaderableNode {
+    /// Tag name, e.g. `p` or `div`
+    pub tag: String,
+    pub id: Option<String>,
+    pub class: Option<String>,
+    /// Length of the node's text, in UTF-16 code units
+    pub text_length: usize,
+    pub score: f64,
+}
+
+/// A reason a page was not considered readerable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReaderableFailure {
+    /// The page has no paragraphs, preformatted text or articles
+    NoContentNodes,
+    /// The visible text does not add up to the minimum score
+    TooLittleText,
+    /// Every node with enough text looks like a sidebar, footer, comment...
+    OnlyUnlikelyCandidates,
+    /// Nodes with enough text are hidden
+    HiddenContent,
+}
+
+/// What the readerable check makes of a single node
+enum NodeVerdict {
+    Hidden(usize),
+    Unlikely(usize),
+    InListItem,
+    TooShort,
+    Counted(usize, f64),
+}
+
+/// Port of Readability.js `isProbablyReaderable`: decide quickly, without
+/// parsing the article, whether the document probably contains one
+pub fn is_probably_readerable(html: &str, options: Option<ReaderableOptions>) -> bool {
+    let document = Html::parse_document(html);
+    let options = options.unwrap_or_default();
+
+    let mut score = 0.0;
+    candidate_nodes(&document).into_iter().any(|node| {
+        if let NodeVerdict::Counted(_, node_score) = judge_node(&node, &options) {
+            score += node_score;
+        }
+        score > options.min_score
+    })
+}
+
+/// Score the document like `is_probably_readerable`, reporting which nodes
+/// contributed and why the page failed
+pub fn assess_readerability(html: &str, options: Option<ReaderableOptions>) -> ReaderableAssessment {
+    let document = Html::parse_document(html);
+    let options = options.unwrap_or_default();
+    let nodes = candidate_nodes(&document);
+
+    let mut score = 0.0;
+    let mut contributing_nodes = Vec::new();
+    let mut hidden_content = false;
+    let mut unlikely_content = false;
+
+    for node in &nodes {
+        match judge_node(node, &options) {
+            NodeVerdict::Counted(text_length, node_score) => {
+                score += node_score;
+                contributing_nodes.push(ReaderableNode {
+                    tag: node.value().name().to_string(),
+                    id: node.value().attr("id").map(str::to_string),
+                    class: node.value().attr("class").map(str::to_string),
+                    text_length,
+                    score: node_score,
+                });
+            }
+            NodeVerdict::Hidden(text_length) => hidden_content |= text_length >= options.min_content_length,
+            NodeVerdict::Unlikely(text_length) => unlikely_content |= text_length >= options.min_content_length,
+            NodeVerdict::InListItem | NodeVerdict::TooShort => {}
+        }
+    }
+
+    // Node scores are never negative, so the running total of
+    // `is_probably_readerable` exceeds the minimum exactly when the sum does
+    let readerable = score > options.min_score;
+
+    let mut failure_reasons = Vec::new();
+    if !readerable {
+        if nodes.is_empty() {
+            failure_reasons.push(ReaderableFailure::NoContentNodes);
+        } else {
+            failure_reasons.push(ReaderableFailure::TooLittleText);
+            if unlikely_content && contributing_nodes.is_empty() {
+                failure_reasons.push(ReaderableFailure::OnlyUnlikelyCandidates);
+            }
+            if hidden_content {
+                failure_reasons.push(ReaderableFailure::HiddenContent);
+            }
+        }
+    }
+
+    ReaderableAssessment {
+        readerable,
+        score,
+        contributing_nodes,
+        failure_reasons,
+    }
+}
+
+/// `p`, `pre` and `article` nodes, followed by divs with `<br>` line breaks
+fn candidate_nodes(document: &Html) -> Vec<ElementRef<'_>> {
+    let node_selector = Selector::parse("p, pre, article").unwrap();
+    let br_selector = Selector::parse("div > br").unwrap();
+
+    let mut nodes: Vec<ElementRef> = document.select(&node_selector).collect();
+    for br in document.select(&br_selector) {
+        if let Some(parent) = br.parent().and_then(ElementRef::wrap) {
+            if !nodes.iter().any(|node| node.id() == parent.id()) {
+                nodes.push(parent);
+            }
+        }
+    }
+    nodes
+}
+
+fn judge_node(node: &ElementRef, options: &ReaderableOptions) -> NodeVerdict {
+    // Measured in UTF-16 code units like `textContent.length`
+    let text_length = || node.text().collect::<String>().trim().encode_utf16().count();
+
+    if !(options.visibility_checker)(node) {
+        return NodeVerdict::Hidden(text_length());
+    }
+
+    let match_string = format!(
+        "{} {}",
+        node.value().attr("class").unwrap_or(""),
+        node.value().attr("id").unwrap_or("")
+    );
+    if is_unlikely_candidate(&match_string) {
+        return NodeVerdict::Unlikely(text_length());
+    }
+
+    if node.value().name() == "p"
+        && node.ancestors().filter_map(ElementRef::wrap).any(|ancestor| ancestor.value().name() == "li")
+    {
+        return NodeVerdict::InListItem;
+    }
+
+    let text_length = text_length();
+    if text_length < options.min_content_length {
+        return NodeVerdict::TooShort;
+    }
+
+    NodeVerdict::Counted(text_length, ((text_length - options.min_content_length) as f64).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assess_readerable_page() {
+        let text = "word ".repeat(100);
+        let html = format!(r#"<html><body>
+            <p class="intro">{}</p><p>short</p><ul><li><p>{}</p></li></ul><p id="body">{}</p>
+            </body></html>"#, text, text, text);
+
+        let assessment = assess_readerability(&html, None);
+        assert!(assessment.readerable);
+        assert!(assessment.failure_reasons.is_empty());
+        assert_eq!(assessment.contributing_nodes.len(), 2);
+        assert_eq!(assessment.contributing_nodes[0].class.as_deref(), Some("intro"));
+        assert_eq!(assessment.contributing_nodes[1].id.as_deref(), Some("body"));
+        assert_eq!(assessment.contributing_nodes[0].text_length, 499);
+        assert!((assessment.score - 2.0 * 359f64.sqrt()).abs() < 1e-9);
+        assert!(is_probably_readerable(&html, None));
+    }
+
+    #[test]
+    fn test_assess_failure_reasons() {
+        let text = "word ".repeat(100);
+
+        let assessment = assess_readerability("<html><body><div>Nothing here</div></body></html>", None);
+        assert!(!assessment.readerable);
+        assert_eq!(assessment.failure_reasons, vec![ReaderableFailure::NoContentNodes]);
+
+        let html = format!(r#"<html><body><p class="comment">{}</p><p style="display: none">{}</p><p>A bit.</p></body></html>"#, text, text);
+        let assessment = assess_readerability(&html, None);
+        assert_eq!(assessment.score, 0.0);
+        assert_eq!(assessment.failure_reasons, vec![
+            ReaderableFailure::TooLittleText,
+            ReaderableFailure::OnlyUnlikelyCandidates,
+            ReaderableFailure::HiddenContent,
+        ]);
+        assert!(!is_probably_readerable(&html, None));
+    }
+
+    #[test]
+    fn test_is_probably_readerable_basic() {
+        let readerable_html = r#"
+            <html>
+            <body>
+                <article>
+                    <h1>Article Title</h1>
+                    <p>This is a substantial article with enough content to be considered readerable. Lorem ipsum dolor sit amet, consectetur adipiscing elit. Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation ullamco laboris nisi ut aliquip ex ea commodo consequat. Duis aute irure dolor in reprehenderit in voluptate velit esse cillum dolore eu fugiat nulla pariatur. Excepteur sint occaecat cupidatat non proident, sunt in culpa qui officia deserunt mollit anim id est laborum. Sed ut perspiciatis unde omnis iste natus error sit voluptatem accusantium doloremque laudantium.</p>
+                </article>
+            </body>
+            </html>
+        "#;
+
+        let non_readerable_html = r#"
+            <html>
+            <body>
+                <div class="navigation">Menu</div>
+                <div class="sidebar">Ads</div>
+                <p>Short content</p>
+            </body>
+            </html>
+        "#;
+
+        assert!(is_probably_readerable(readerable_html, None));
+        assert!(!is_probably_readerable(non_readerable_html, None));
+    }
+
+    #[test]
+    fn test_is_probably_readerable_with_options() {
+        let html = r#"
+            <html>
+            <body>
+                <p>This is a medium-length article that might be readerable with different thresholds. Lorem ipsum dolor sit amet, consectetur adipiscing elit. Sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.</p>
+            </body>
+            </html>
+        "#;
+
+        // Default options (high threshold)
+        assert!(!is_probably_readerable(html, None));
+
+        // Lower thresholds
+        let low_threshold_options = ReaderableOptions {
+            min_content_length: 50,
+            min_score: 10.0,
+            ..Default::default()
+        };
+        assert!(is_probably_readerable(html, Some(low_threshold_options)));
+    }
+
+    // Ported from Readability.js test/test-isProbablyReaderable.js
+    #[test]
+    fn test_is_probably_readerable_document_sizes() {
+        let make_doc = |repeat: usize| format!(r#"<html><body><p id="main">{}</p></body></html>"#, "hello there ".repeat(repeat));
+        let very_small_doc = r#"<html><body><p id="main">hello there</p></body></html>"#; // content length: 11
+        let small_doc = make_doc(11); // content length: 131
+        let large_doc = make_doc(12); // content length: 143
+        let very_large_doc = make_doc(50); // content length: 599
+
+        // Only large documents are readerable with the default options
+        assert!(!is_probably_readerable(very_small_doc, None));
+        assert!(!is_probably_readerable(&small_doc, None));
+        assert!(!is_probably_readerable(&large_doc, None));
+        assert!(is_probably_readerable(&very_large_doc, None));
+
+        let options = ReaderableOptions { min_content_length: 120, min_score: 0.0, ..Default::default() };
+        assert!(!is_probably_readerable(very_small_doc, Some(options.clone())));
+        assert!(is_probably_readerable(&small_doc, Some(options.clone())));
+        assert!(is_probably_readerable(&large_doc, Some(options.clone())));
+        assert!(is_probably_readerable(&very_large_doc, Some(options)));
+
+        let options = ReaderableOptions { min_content_length: 200, min_score: 0.0, ..Default::default() };
+        assert!(!is_probably_readerable(&small_doc, Some(options.clone())));
+        assert!(!is_probably_readerable(&large_doc, Some(options.clone())));
+        assert!(is_probably_readerable(&very_large_doc, Some(options)));
+
+        let options = ReaderableOptions { min_content_length: 0, min_score: 11.5, ..Default::default() };
+        assert!(!is_probably_readerable(&small_doc, Some(options.clone())));
+        assert!(is_probably_readerable(&large_doc, Some(options.clone())));
+        assert!(is_probably_readerable(&very_large_doc, Some(options)));
+
+        // The visibility checker decides which nodes are considered
+        let options = ReaderableOptions { visibility_checker: |_| false, ..Default::default() };
+        assert!(!is_probably_readerable(&very_large_doc, Some(options)));
+    }
+
+    #[test]
+    fn test_is_probably_readerable_skipped_nodes() {
+        let text = "word ".repeat(200);
+        assert!(is_probably_readerable(&format!("<html><body><div>{}<br>{}</div></body></html>", text, text), None));
+        assert!(!is_probably_readerable(&format!("<html><body><ul><li><p>{}</p></li></ul></body></html>", text), None));
+        assert!(!is_probably_readerable(&format!(r#"<html><body><p class="sidebar">{}</p></body></html>"#, text), None));
+        assert!(!is_probably_readerable(&format!(r#"<html><body><p hidden>{}</p></body></html>"#, text), None));
+    }
+
+    /// Pages modelled on the Readability.js test corpus, with the answer of
+    /// `isProbablyReaderable` for each
+    #[test]
+    fn test_readerable_fixtures() {
+        let sentence = "The quick brown fox jumps over the lazy dog. ";
+        let short = sentence.repeat(2);
+        let medium = sentence.repeat(10);
+        let long = sentence.repeat(14);
+
+        let fixtures = [
+            ("news-article", format!(r#"<html><body><header><nav><a href="/">Home</a></nav></header>
+                <article><h1>Title</h1><p>{medium}</p><p>{medium}</p><p>{short}</p></article>
+                <footer>Copyright</footer></body></html>"#), true),
+            ("single-long-paragraph", format!("<html><body><p>{long}</p></body></html>"), true),
+            ("article-without-paragraphs", format!("<html><body><article>{long}</article></body></html>"), true),
+            ("preformatted", format!("<html><body><pre>{long}</pre></body></html>"), true),
+            ("br-separated-div", format!("<html><body><div>{medium}<br><br>{medium}</div></body></html>"), true),
+            ("ok-maybe-class", format!(r#"<html><body><p class="comment article-body">{long}</p></body></html>"#), true),
+            ("short-paragraphs", format!("<html><body>{}</body></html>", format!("<p>{short}</p>").repeat(20)), false),
+            ("single-medium-paragraph", format!("<html><body><p>{medium}</p></body></html>"), false),
+            ("comments", format!(r#"<html><body><div id="comments"><p class="comment">{long}</p>
+                <p class="comment">{long}</p></div></body></html>"#), false),
+            ("sidebar", format!(r#"<html><body><p id="sidebar">{long}</p><p>{short}</p></body></html>"#), false),
+            ("hidden", format!(r#"<html><body><p style="display:none">{long}</p><p aria-hidden="true">{long}</p></body></html>"#), false),
+            ("list-of-paragraphs", format!("<html><body><ul><li><p>{long}</p></li><li><p>{long}</p></li></ul></body></html>"), false),
+            ("link-index", format!("<html><body><ul>{}</ul></body></html>", format!(r#"<li><a href="/x">{short}</a></li>"#).repeat(30)), false),
+            ("empty", "<html><body></body></html>".to_string(), false),
+        ];
+
+        let mismatches: Vec<&str> = fixtures.iter()
+            .filter(|(_, html, expected)| is_probably_readerable(html, None) != *expected)
+            .map(|(name, _, _)| *name)
+            .collect();
+        assert!(mismatches.is_empty(), "isProbablyReaderable mismatches: {:?}", mismatches);
+
+        for (name, html, expected) in &fixtures {
+            assert_eq!(assess_readerability(html, None).readerable, *expected, "{}", name);
+        }
+    }
+}
@@ -4,6 +4,7 @@ use regex::Regex;
 use std::sync::OnceLock;
 
 /// Regular expressions for identifying content patterns
+#[allow(dead_code)] // Ported from Readability.js, not used by the parser yet
 pub struct ReadabilityRegexps {
     pub unlikely_candidates: Regex,
     pub ok_maybe_its_candidate: Regex,
@@ -27,6 +28,19 @@ pub struct ReadabilityRegexps {
     pub json_ld_article_types: Regex,
     pub ad_words: Regex,
     pub loading_words: Regex,
+    pub title_separator: Regex,
+    pub title_hierarchical_separator: Regex,
+    pub title_first_part: Regex,
+    pub html_entity: Regex,
+    pub byline_date: Regex,
+    pub byline_separator: Regex,
+    pub twitter_handle: Regex,
+    pub staff_suffix: Regex,
+    pub paywall_container: Regex,
+    pub paywall_marker: Regex,
+    pub creative_commons_url: Regex,
+    pub creative_commons_zero: Regex,
+    pub creative_commons_name: Regex,
 }
 
 impl ReadabilityRegexps {
@@ -53,7 +67,7 @@ impl ReadabilityRegexps {
             ).unwrap(),
             
             byline: Regex::new(
-                r"(?i)byline|author|dateline|written\s*by|p-author|by\s+\w+"
+                r"(?i)byline|author|dateline|written\s*by|p-author|\bby\s+\w+"
             ).unwrap(),
             
             replace_fonts: Regex::new(
@@ -122,6 +136,61 @@ impl ReadabilityRegexps {
             loading_words: Regex::new(
                 r"(?i)^((loading|正在加载|Загрузка|chargement|cargando)(…|\.\.\.)?)$"
             ).unwrap(),
+            
+            // Separators between the article name and the site name in <title>
+            title_separator: Regex::new(
+                r"\s[\|\-–—\\/>»]\s"
+            ).unwrap(),
+            
+            title_hierarchical_separator: Regex::new(
+                r"\s[\\/>»]\s"
+            ).unwrap(),
+            
+            title_first_part: Regex::new(
+                r"^[^\|\-–—\\/>»]*[\|\-–—\\/>»]"
+            ).unwrap(),
+            
+            // Named and numeric character references left in attribute values
+            html_entity: Regex::new(
+                r"&(?:(quot|amp|apos|lt|gt)|#[xX]([0-9a-fA-F]+)|#([0-9]+));"
+            ).unwrap(),
+            
+            // Dates and times written next to author names in bylines
+            byline_date: Regex::new(
+                r"(?i)(?:\b(?:updated|published|posted)\b:?\s*(?:on\s+)?)?(?:\b(?:jan|feb|mar|apr|may|jun|jul|aug|sep|sept|oct|nov|dec)[a-z]*\.?\s+\d{1,2}(?:st|nd|rd|th)?,?\s+\d{4}|\b\d{1,2}\s+(?:jan|feb|mar|apr|may|jun|jul|aug|sep|sept|oct|nov|dec)[a-z]*\.?,?\s+\d{4}|\b\d{4}-\d{2}-\d{2}|\b\d{1,2}/\d{1,2}/\d{2,4})(?:,?\s*(?:at\s+)?\d{1,2}:\d{2}(?:\s*[ap]\.?m\.?)?(?-i:\s+[A-Z]{2,4}\b)?)?"
+            ).unwrap(),
+            
+            byline_separator: Regex::new(
+                r"(?i)\s*(?:,|&|;|\band\b)\s*"
+            ).unwrap(),
+            
+            twitter_handle: Regex::new(
+                r"\(?@\w{1,15}\)?"
+            ).unwrap(),
+            
+            staff_suffix: Regex::new(
+                r"(?i)(?:^|[\s,\-–—|]+)staff(?:\s+(?:writer|reporter|report|editor))?$"
+            ).unwrap(),
+            
+            paywall_container: Regex::new(
+                r"(?i)pay-?wall|reg-?wall|subscriber-only|subscribers-only|subscription-required|premium-content|metered-?content|locked-content|gated-content|article-locked|piano-offer|tp-modal"
+            ).unwrap(),
+            
+            paywall_marker: Regex::new(
+                r"(?i)(?:subscribe|sign in|log in|register)\b.{0,40}\bto (?:continue|keep) reading|to read (?:the|this) (?:full|entire|rest of the) (?:article|story)|(?:article|story|content) is (?:only )?(?:for|available to) (?:subscribers|members)|already a subscriber\?|you have reached your (?:free )?(?:article )?limit"
+            ).unwrap(),
+            
+            creative_commons_url: Regex::new(
+                r"(?i)(?:creativecommons\.org/licenses|licensebuttons\.net/l|i\.creativecommons\.org/l)/(by|by-sa|by-nd|by-nc|by-nc-sa|by-nc-nd)/(\d\.\d)(?:/([a-z]{2,3})(?:/|$))?"
+            ).unwrap(),
+            
+            creative_commons_zero: Regex::new(
+                r"(?i)(?:creativecommons\.org|licensebuttons\.net)/(?:publicdomain|p)/zero/1\.0|\bCC0\b"
+            ).unwrap(),
+            
+            creative_commons_name: Regex::new(
+                r"(?i)\bCC[\s-]?(BY(?:[\s-](?:NC|SA|ND))*)[\s-]+(\d\.\d)\b"
+            ).unwrap(),
         }
     }
 }
@@ -161,6 +230,7 @@ pub fn is_video_url(url: &str) -> bool {
 }
 
 /// Normalize whitespace in text
+#[allow(dead_code)] // Ported from Readability.js, not used by the parser yet
 pub fn normalize_whitespace(text: &str) -> String {
     get_regexps().normalize.replace_all(text, " ").to_string()
 }
@@ -230,5 +300,6 @@ mod tests {
         assert!(is_byline("by author"));
         assert!(is_byline("written by John Doe"));
         assert!(!is_byline("random text"));
+        assert!(!is_byline("lobby main"));
     }
 }
\ No newline at end of file
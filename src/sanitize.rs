@@ -0,0 +1,251 @@
+//! Allowlist sanitizer that makes the article content safe to inject into a page
+
+use std::collections::BTreeMap;
+
+use regex::Regex;
+use scraper::{ElementRef, Html, Node};
+use crate::regexps::is_video_url;
+
+/// Elements serialized without a closing tag
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr",
+];
+
+/// Elements removed with their content when not allowed, rather than
+/// replaced by their children
+const REMOVED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "form", "iframe", "object", "embed", "textarea", "select",
+    "button", "title", "head", "svg", "math",
+];
+
+/// Elements only kept when their source is an allowed video
+const EMBED_TAGS: &[&str] = &["iframe", "object", "embed"];
+
+/// Attributes holding a URL whose scheme is checked
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "cite", "poster", "data", "longdesc", "action", "formaction", "background"];
+
+/// Options for `sanitize_html`. Anything not allowed is removed; event
+/// handlers are removed whatever the allowlist says.
+#[derive(Debug, Clone)]
+pub struct SanitizeOptions {
+    /// Tags to keep; other elements are replaced by their content, or
+    /// removed with it when they are scripts, forms, embeds and the like
+    pub allowed_tags: Vec<String>,
+    /// Attributes kept on every allowed tag
+    pub allowed_attributes: Vec<String>,
+    /// Further attributes kept on particular tags
+    pub tag_attributes: BTreeMap<String, Vec<String>>,
+    /// Schemes allowed in URL attributes; relative URLs are always allowed
+    pub allowed_url_schemes: Vec<String>,
+    /// Sources of `iframe`, `object` and `embed` elements to keep; defaults
+    /// to the video sites `is_video_url` knows
+    pub allowed_video_regex: Option<Regex>,
+}
+
+impl Default for SanitizeOptions {
+    fn default() -> Self {
+        let tags = [
+            "a", "abbr", "article", "aside", "audio", "b", "bdi", "bdo", "blockquote", "br", "caption", "cite",
+            "code", "col", "colgroup", "dd", "del", "details", "dfn", "div", "dl", "dt", "em", "embed", "figcaption",
+            "figure", "footer", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "i", "iframe", "img", "ins",
+            "kbd", "li", "mark", "object", "ol", "p", "param", "picture", "pre", "q", "rp", "rt", "ruby", "s",
+            "samp", "section", "small", "source", "span", "strong", "sub", "summary", "sup", "table", "tbody",
+            "td", "tfoot", "th", "thead", "time", "tr", "track", "u", "ul", "var", "video", "wbr",
+        ];
+        let tag_attributes: &[(&str, &[&str])] = &[
+            ("a", &["href", "rel", "hreflang"]),
+            ("audio", &["src", "controls", "loop", "muted", "preload"]),
+            ("blockquote", &["cite"]),
+            ("col", &["span"]),
+            ("colgroup", &["span"]),
+            ("del", &["cite", "datetime"]),
+            ("embed", &["src", "type", "width", "height"]),
+            ("iframe", &["src", "width", "height", "allowfullscreen", "frameborder"]),
+            ("img", &["src", "srcset", "sizes", "alt", "width", "height"]),
+            ("ins", &["cite", "datetime"]),
+            ("li", &["value"]),
+            ("object", &["data", "type", "width", "height"]),
+            ("ol", &["start", "reversed", "type"]),
+            ("param", &["name", "value"]),
+            ("q", &["cite"]),
+            ("source", &["src", "srcset", "sizes", "type", "media"]),
+            ("td", &["colspan", "rowspan", "headers"]),
+            ("th", &["colspan", "rowspan", "headers", "scope"]),
+            ("time", &["datetime"]),
+            ("track", &["src", "kind", "srclang", "label", "default"]),
+            ("video", &["src", "poster", "controls", "width", "height", "loop", "muted", "playsinline", "preload"]),
+        ];
+
+        Self {
+            allowed_tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            allowed_attributes: ["id", "class", "title", "lang", "dir"].iter().map(|attr| attr.to_string()).collect(),
+            tag_attributes: tag_attributes.iter()
+                .map(|(tag, attrs)| (tag.to_string(), attrs.iter().map(|attr| attr.to_string()).collect()))
+                .collect(),
+            allowed_url_schemes: ["http", "https", "mailto"].iter().map(|scheme| scheme.to_string()).collect(),
+            allowed_video_regex: None,
+        }
+    }
+}
+
+/// Sanitize an HTML fragment against the allowlist in `options`
+pub fn sanitize_html(html: &str, options: &SanitizeOptions) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut output = String::new();
+    write_children(&fragment.root_element(), options, &mut output);
+    output
+}
+
+fn write_children(parent: &ElementRef, options: &SanitizeOptions, output: &mut String) {
+    for child in parent.children() {
+        match child.value() {
+            Node::Text(text) => output.push_str(&escape_text(text)),
+            Node::Element(_) => write_element(&ElementRef::wrap(child).unwrap(), options, output),
+            _ => {}
+        }
+    }
+}
+
+fn write_element(element: &ElementRef, options: &SanitizeOptions, output: &mut String) {
+    let name = element.value().name();
+
+    if !options.allowed_tags.iter().any(|tag| tag == name) {
+        if !REMOVED_TAGS.contains(&name) {
+            write_children(element, options, output);
+        }
+        return;
+    }
+
+    if EMBED_TAGS.contains(&name) {
+        let source = element.value().attr("src").or_else(|| element.value().attr("data")).unwrap_or("");
+        let is_video = match &options.allowed_video_regex {
+            Some(regex) => regex.is_match(source),
+            None => is_video_url(source),
+        };
+        if !is_video || !is_allowed_url(source, options) {
+            return;
+        }
+    }
+
+    let tag_attributes = options.tag_attributes.get(name);
+    // Attributes are kept in a stable order, as the parser does not keep
+    // them in source order
+    let mut attrs: Vec<(&str, &str)> = element.value().attrs().collect();
+    attrs.sort_unstable();
+
+    let mut attributes = String::new();
+    for (attr, value) in attrs {
+        let allowed = options.allowed_attributes.iter().any(|allowed| allowed == attr)
+            || tag_attributes.is_some_and(|allowed| allowed.iter().any(|allowed| allowed == attr));
+        if !allowed || attr.starts_with("on") {
+            continue;
+        }
+        if URL_ATTRIBUTES.contains(&attr) && !is_allowed_url(value, options) {
+            continue;
+        }
+        if attr == "srcset" && !srcset_urls(value).all(|url| is_allowed_url(url, options)) {
+            continue;
+        }
+        attributes.push_str(&format!(" {}=\"{}\"", attr, escape_attribute(value)));
+    }
+
+    // Images and embeds are nothing without their source
+    if matches!(name, "img" | "iframe" | "embed") && !attributes.contains(" src=\"") {
+        return;
+    }
+
+    output.push('<');
+    output.push_str(name);
+    output.push_str(&attributes);
+    output.push('>');
+    if VOID_TAGS.contains(&name) {
+        return;
+    }
+    write_children(element, options, output);
+    output.push_str(&format!("</{}>", name));
+}
+
+/// Whether a URL is relative or uses an allowed scheme. Browsers ignore
+/// whitespace and control characters in schemes, so `java\tscript:` counts
+/// as `javascript:`.
+fn is_allowed_url(url: &str, options: &SanitizeOptions) -> bool {
+    let url: String = url.chars().filter(|c| !c.is_whitespace() && !c.is_control()).collect();
+    let scheme_end = match url.find(':') {
+        Some(end) if !url[..end].contains(['/', '?', '#']) => end,
+        _ => return true,
+    };
+    let scheme = url[..scheme_end].to_lowercase();
+    options.allowed_url_schemes.iter().any(|allowed| allowed.eq_ignore_ascii_case(&scheme))
+}
+
+fn srcset_urls(srcset: &str) -> impl Iterator<Item = &str> {
+    srcset.split(',').filter_map(|candidate| candidate.split_whitespace().next())
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn escape_attribute(value: &str) -> String {
+    value.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_html() {
+        let options = SanitizeOptions::default();
+        let html = r#"<p onclick="steal()" style="color: red" class="intro">Hi <font>there</font> &amp; <a href=" JaVa&#9;Script:alert(1)">bad</a>
+            <a href="https://example.com/" target="_blank">good</a> <a href="/relative">relative</a></p>
+            <form action="/login"><p>Log in</p><input name="password"></form><script>alert(1)</script>
+            <img src="data:image/png;base64,AAAA" alt="inline"><img src="https://example.com/a.png" srcset="https://example.com/b.png 2x" onerror="x()">"#;
+
+        assert_eq!(
+            sanitize_html(html, &options),
+            r#"<p class="intro">Hi there &amp; <a>bad</a>
+            <a href="https://example.com/">good</a> <a href="/relative">relative</a></p>
+            
+            <img src="https://example.com/a.png" srcset="https://example.com/b.png 2x">"#
+        );
+    }
+
+    #[test]
+    fn test_sanitize_embeds() {
+        let options = SanitizeOptions::default();
+        let html = r#"<iframe src="https://www.youtube.com/embed/abc" width="560" sandbox=""></iframe>
+            <iframe src="https://ads.example.com/frame"></iframe><object data="https://evil.example.com/x.swf"><p>Fallback</p></object>"#;
+        assert_eq!(
+            sanitize_html(html, &options),
+            "<iframe src=\"https://www.youtube.com/embed/abc\" width=\"560\"></iframe>\n            "
+        );
+
+        let options = SanitizeOptions {
+            allowed_video_regex: Some(Regex::new(r"^https://video\.example\.com/").unwrap()),
+            ..Default::default()
+        };
+        assert_eq!(
+            sanitize_html(r#"<embed src="https://video.example.com/v.mp4"><embed src="https://www.youtube.com/v/abc">"#, &options),
+            r#"<embed src="https://video.example.com/v.mp4">"#
+        );
+    }
+
+    #[test]
+    fn test_sanitize_custom_allowlist() {
+        let options = SanitizeOptions {
+            allowed_tags: vec!["p".to_string(), "a".to_string()],
+            allowed_attributes: Vec::new(),
+            tag_attributes: BTreeMap::from([("a".to_string(), vec!["href".to_string(), "onclick".to_string()])]),
+            allowed_url_schemes: vec!["https".to_string()],
+            allowed_video_regex: None,
+        };
+        let html = r#"<div id="x"><p>One <em>two</em> <a href="mailto:a@example.com" onclick="x()">mail</a></p></div>"#;
+        assert_eq!(sanitize_html(html, &options), "<p>One two <a>mail</a></p>");
+    }
+}
@@ -27,11 +27,19 @@ impl ContentScore {
     }
 }
 
+impl Default for ContentScore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Content scorer for evaluating DOM elements
+#[allow(dead_code)] // Ported from Readability.js, not used by the parser yet
 pub struct ContentScorer {
     scores: HashMap<String, ContentScore>,
 }
 
+#[allow(dead_code)] // Ported from Readability.js, not used by the parser yet
 impl ContentScorer {
     pub fn new() -> Self {
         Self {
@@ -268,6 +276,7 @@ impl ContentScorer {
 }
 
 /// Calculate the text similarity between two strings
+#[allow(dead_code)] // Ported from Readability.js, not used by the parser yet
 pub fn text_similarity(text_a: &str, text_b: &str) -> f64 {
     let tokens_a: Vec<&str> = text_a.split_whitespace().collect();
     let tokens_b: Vec<&str> = text_b.split_whitespace().collect();
@@ -292,6 +301,7 @@ pub fn text_similarity(text_a: &str, text_b: &str) -> f64 {
 }
 
 /// Check if an element should be removed based on its characteristics
+#[allow(dead_code)] // Ported from Readability.js, not used by the parser yet
 pub fn should_remove_element(element: &ElementRef, tag_name: &str) -> bool {
     let class_and_id = format!("{} {}", 
         element.value().attr("class").unwrap_or(""),
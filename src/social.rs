@@ -0,0 +1,238 @@
+//! Open Graph and Twitter Card metadata for the Readability parser
+
+use scraper::{Html, Selector};
+use serde::{Deserialize, Serialize};
+use crate::utils::{parse_dimension, unescape_html_entities};
+
+/// Open Graph (`og:*`) metadata of a page
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenGraph {
+    pub title: Option<String>,
+    /// `og:type`, e.g. `article` or `website`
+    #[serde(rename = "type")]
+    pub og_type: Option<String>,
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub site_name: Option<String>,
+    pub locale: Option<String>,
+    /// Other locales the page is available in (`og:locale:alternate`)
+    pub locale_alternates: Vec<String>,
+    pub images: Vec<OpenGraphMedia>,
+    pub videos: Vec<OpenGraphMedia>,
+    pub audio: Vec<OpenGraphMedia>,
+}
+
+/// An image, video or audio object with its structured properties
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenGraphMedia {
+    pub url: String,
+    pub secure_url: Option<String>,
+    /// MIME type
+    #[serde(rename = "type")]
+    pub mime_type: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub alt: Option<String>,
+}
+
+/// Twitter Card (`twitter:*`) metadata of a page
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TwitterCard {
+    /// Card type, e.g. `summary_large_image` or `player`
+    pub card: Option<String>,
+    /// `@username` of the website
+    pub site: Option<String>,
+    /// `@username` of the content creator
+    pub creator: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    pub image_alt: Option<String>,
+    pub player: Option<String>,
+    pub player_width: Option<u32>,
+    pub player_height: Option<u32>,
+}
+
+/// Read the Open Graph properties of a document, in document order so that
+/// structured properties like `og:image:width` apply to the preceding image
+pub fn extract_open_graph(document: &Html) -> Option<OpenGraph> {
+    let mut og = OpenGraph::default();
+    let mut found = false;
+
+    for (key, value) in meta_properties(document, "og:") {
+        found = true;
+        match key.as_str() {
+            "title" => og.title = Some(value),
+            "type" => og.og_type = Some(value),
+            "url" => og.url = Some(value),
+            "description" => og.description = Some(value),
+            "site_name" => og.site_name = Some(value),
+            "locale" => og.locale = Some(value),
+            "locale:alternate" => og.locale_alternates.push(value),
+            _ => {
+                for (prefix, media) in [("image", &mut og.images), ("video", &mut og.videos), ("audio", &mut og.audio)] {
+                    if let Some(property) = key.strip_prefix(prefix) {
+                        apply_media_property(media, property, value);
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    if found { Some(og) } else { None }
+}
+
+/// Read the Twitter Card properties of a document
+pub fn extract_twitter_card(document: &Html) -> Option<TwitterCard> {
+    let mut card = TwitterCard::default();
+    let mut found = false;
+
+    for (key, value) in meta_properties(document, "twitter:") {
+        found = true;
+        match key.as_str() {
+            "card" => card.card = Some(value),
+            "site" => card.site = Some(value),
+            "creator" => card.creator = Some(value),
+            "title" => card.title = Some(value),
+            "description" => card.description = Some(value),
+            "image" | "image:src" => {
+                card.image.get_or_insert(value);
+            }
+            "image:alt" => card.image_alt = Some(value),
+            "player" => card.player = Some(value),
+            "player:width" => card.player_width = parse_dimension(&value),
+            "player:height" => card.player_height = parse_dimension(&value),
+            _ => {}
+        }
+    }
+
+    if found { Some(card) } else { None }
+}
+
+/// `(property without prefix, content)` of every `<meta>` whose `property`
+/// or `name` starts with the prefix
+fn meta_properties(document: &Html, prefix: &str) -> Vec<(String, String)> {
+    let meta_selector = Selector::parse("meta[content]").unwrap();
+
+    document.select(&meta_selector)
+        .filter_map(|meta| {
+            let key = meta.value().attr("property").or_else(|| meta.value().attr("name"))?;
+            let key = key.trim().to_lowercase();
+            let key = key.strip_prefix(prefix)?.to_string();
+            let value = unescape_html_entities(meta.value().attr("content")?.trim());
+            if value.is_empty() { None } else { Some((key, value)) }
+        })
+        .collect()
+}
+
+fn apply_media_property(media: &mut Vec<OpenGraphMedia>, property: &str, value: String) {
+    // `og:image` starts a new object; `og:image:url` is an alias that only
+    // does when the current object already has its URL
+    if property.is_empty() || property == ":url" {
+        match media.last_mut() {
+            Some(current) if current.url.is_empty() => current.url = value,
+            Some(current) if property == ":url" && current.url == value => {}
+            _ => media.push(OpenGraphMedia { url: value, ..Default::default() }),
+        }
+        return;
+    }
+
+    let current = match media.last_mut() {
+        Some(current) => current,
+        None => {
+            // Structured properties before the URL still describe an object
+            media.push(OpenGraphMedia::default());
+            media.last_mut().unwrap()
+        }
+    };
+    match property {
+        ":secure_url" => current.secure_url = Some(value),
+        ":type" => current.mime_type = Some(value),
+        ":width" => current.width = parse_dimension(&value),
+        ":height" => current.height = parse_dimension(&value),
+        ":alt" => current.alt = Some(value),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_open_graph() {
+        let document = Html::parse_document(r#"<html><head>
+            <meta property="og:title" content="Tom &amp; Jerry">
+            <meta property="og:type" content="article">
+            <meta property="og:url" content="https://example.com/story">
+            <meta property="og:locale" content="en_US">
+            <meta property="og:locale:alternate" content="de_DE">
+            <meta property="og:image" content="https://example.com/a.jpg">
+            <meta property="og:image:width" content="1200">
+            <meta property="og:image:height" content="630">
+            <meta property="og:image" content="https://example.com/b.jpg">
+            <meta property="og:image:alt" content="Second image">
+            <meta property="og:video:url" content="https://example.com/v.mp4">
+            <meta property="og:video:type" content="video/mp4">
+        </head></html>"#);
+
+        let og = extract_open_graph(&document).unwrap();
+        assert_eq!(og.title, Some("Tom & Jerry".to_string()));
+        assert_eq!(og.og_type, Some("article".to_string()));
+        assert_eq!(og.locale_alternates, vec!["de_DE"]);
+        assert_eq!(og.images, vec![
+            OpenGraphMedia { url: "https://example.com/a.jpg".to_string(), width: Some(1200), height: Some(630), ..Default::default() },
+            OpenGraphMedia { url: "https://example.com/b.jpg".to_string(), alt: Some("Second image".to_string()), ..Default::default() },
+        ]);
+        assert_eq!(og.videos[0].mime_type, Some("video/mp4".to_string()));
+        assert!(og.audio.is_empty());
+    }
+
+    #[test]
+    fn test_open_graph_url_alias() {
+        let document = Html::parse_document(r#"<html><head>
+            <meta property="og:image" content="https://example.com/a.jpg">
+            <meta property="og:image:url" content="https://example.com/a.jpg">
+            <meta property="og:image:width" content="1200">
+            <meta property="og:image:url" content="https://example.com/b.jpg">
+            <meta property="og:video:type" content="video/mp4">
+            <meta property="og:video:url" content="https://example.com/v.mp4">
+        </head></html>"#);
+
+        let og = extract_open_graph(&document).unwrap();
+        assert_eq!(og.images, vec![
+            OpenGraphMedia { url: "https://example.com/a.jpg".to_string(), width: Some(1200), ..Default::default() },
+            OpenGraphMedia { url: "https://example.com/b.jpg".to_string(), ..Default::default() },
+        ]);
+        assert_eq!(og.videos, vec![OpenGraphMedia {
+            url: "https://example.com/v.mp4".to_string(),
+            mime_type: Some("video/mp4".to_string()),
+            ..Default::default()
+        }]);
+    }
+
+    #[test]
+    fn test_extract_twitter_card() {
+        let document = Html::parse_document(r#"<html><head>
+            <meta name="twitter:card" content="summary_large_image">
+            <meta name="twitter:site" content="@example">
+            <meta name="twitter:creator" content="@janedoe">
+            <meta name="twitter:image:src" content="https://example.com/t.jpg">
+            <meta property="twitter:player:width" content="640">
+        </head></html>"#);
+
+        let card = extract_twitter_card(&document).unwrap();
+        assert_eq!(card.card, Some("summary_large_image".to_string()));
+        assert_eq!(card.creator, Some("@janedoe".to_string()));
+        assert_eq!(card.image, Some("https://example.com/t.jpg".to_string()));
+        assert_eq!(card.player_width, Some(640));
+    }
+
+    #[test]
+    fn test_missing_metadata() {
+        let document = Html::parse_document(r#"<html><head><meta name="description" content="x"></head></html>"#);
+        assert_eq!(extract_open_graph(&document), None);
+        assert_eq!(extract_twitter_card(&document), None);
+    }
+}
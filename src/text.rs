@@ -0,0 +1,276 @@
+//! Structure-preserving plain text rendering of the extracted article content
+
+use scraper::{ElementRef, Html, Node};
+use crate::utils::{normalize_whitespace, RENDER_BLOCK_TAGS};
+
+/// Width of the rule `<hr>` is rendered as when the text is not wrapped
+const DEFAULT_RULE_WIDTH: usize = 40;
+
+/// Marks `<br>` in inline text until paragraphs are split into lines
+const LINE_BREAK: char = '\u{2028}';
+
+/// Render an HTML fragment as plain text with paragraph breaks, list markers,
+/// underlined headings and verbatim preformatted blocks. Paragraphs are
+/// hard-wrapped at `wrap_width` characters when given.
+pub fn html_to_text(html: &str, wrap_width: Option<usize>) -> String {
+    let fragment = Html::parse_fragment(html);
+    let renderer = TextRenderer { wrap_width: wrap_width.filter(|width| *width > 0) };
+    let mut text = renderer.render_blocks(&fragment.root_element(), 0).join("\n\n");
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    text
+}
+
+/// A heading underlined with `=` or `-` like setext Markdown
+pub fn underline(heading: &str, level: usize) -> String {
+    let rule = if level == 1 { "=" } else { "-" };
+    let width = heading.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+    format!("{}\n{}", heading, rule.repeat(width))
+}
+
+struct TextRenderer {
+    wrap_width: Option<usize>,
+}
+
+impl TextRenderer {
+    /// Render the children of a container as blocks, gathering runs of inline
+    /// content into paragraphs. `indent` is the width taken by the enclosing
+    /// list markers and quote prefixes, which wrapping has to leave room for.
+    fn render_blocks(&self, container: &ElementRef, indent: usize) -> Vec<String> {
+        let mut blocks = Vec::new();
+        let mut paragraph = String::new();
+
+        let flush = |paragraph: &mut String, blocks: &mut Vec<String>| {
+            let text = self.wrap(paragraph, indent);
+            if !text.is_empty() {
+                blocks.push(text);
+            }
+            paragraph.clear();
+        };
+
+        for child in container.children() {
+            match child.value() {
+                Node::Text(text) => paragraph.push_str(text),
+                Node::Element(_) => {
+                    let element = ElementRef::wrap(child).unwrap();
+                    if RENDER_BLOCK_TAGS.contains(&element.value().name()) {
+                        flush(&mut paragraph, &mut blocks);
+                        blocks.extend(self.render_block(&element, indent));
+                    } else {
+                        paragraph.push_str(&render_inline(&element));
+                    }
+                }
+                _ => {}
+            }
+        }
+        flush(&mut paragraph, &mut blocks);
+
+        blocks
+    }
+
+    fn render_block(&self, element: &ElementRef, indent: usize) -> Vec<String> {
+        let tag = element.value().name();
+        match tag {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let text = self.wrap(&render_inline_children(element), indent);
+                if text.is_empty() {
+                    return Vec::new();
+                }
+                vec![underline(&text, tag[1..].parse().unwrap())]
+            }
+            "ul" | "ol" => {
+                let list = self.render_list(element, indent);
+                if list.is_empty() { Vec::new() } else { vec![list] }
+            }
+            "blockquote" => {
+                let quote = self.render_blocks(element, indent + 2).join("\n\n");
+                if quote.is_empty() {
+                    return Vec::new();
+                }
+                vec![prefix_lines(&quote, "> ", "> ")]
+            }
+            "pre" => {
+                let text = element.text().collect::<String>();
+                let text = text.strip_prefix('\n').unwrap_or(&text).trim_end();
+                if text.is_empty() { Vec::new() } else { vec![text.to_string()] }
+            }
+            "table" => {
+                let table = render_table(element);
+                if table.is_empty() { Vec::new() } else { vec![table] }
+            }
+            "hr" => {
+                let width = self.wrap_width.map(|width| width.saturating_sub(indent)).unwrap_or(DEFAULT_RULE_WIDTH);
+                vec!["-".repeat(width.max(3))]
+            }
+            _ => self.render_blocks(element, indent),
+        }
+    }
+
+    fn render_list(&self, list: &ElementRef, indent: usize) -> String {
+        let ordered = list.value().name() == "ol";
+        let mut number = list.value().attr("start")
+            .and_then(|start| start.trim().parse::<usize>().ok())
+            .unwrap_or(1);
+        let mut items = Vec::new();
+
+        for item in list.children().filter_map(ElementRef::wrap) {
+            if item.value().name() != "li" {
+                continue;
+            }
+            let marker = if ordered { format!("{}. ", number) } else { "* ".to_string() };
+            number += 1;
+
+            let content = self.render_blocks(&item, indent + marker.len()).join("\n");
+            let padding = " ".repeat(marker.len());
+            items.push(prefix_lines(&content, &marker, &padding));
+        }
+
+        items.join("\n")
+    }
+
+    /// Collapse whitespace and wrap a paragraph, keeping `<br>` line breaks
+    fn wrap(&self, text: &str, indent: usize) -> String {
+        // Newlines in the source are whitespace, only <br> breaks lines
+        let lines: Vec<String> = text.split(LINE_BREAK)
+            .map(normalize_whitespace)
+            .collect();
+        let lines = trim_empty_lines(lines);
+
+        match self.wrap_width {
+            Some(width) => lines.iter()
+                .map(|line| wrap_line(line, width.saturating_sub(indent).max(1)))
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => lines.join("\n"),
+        }
+    }
+}
+
+fn render_inline_children(element: &ElementRef) -> String {
+    let mut output = String::new();
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) => output.push_str(text),
+            Node::Element(_) => {
+                let child = ElementRef::wrap(child).unwrap();
+                if RENDER_BLOCK_TAGS.contains(&child.value().name()) {
+                    output.push(' ');
+                    output.push_str(&render_inline_children(&child));
+                    output.push(' ');
+                } else {
+                    output.push_str(&render_inline(&child));
+                }
+            }
+            _ => {}
+        }
+    }
+    output
+}
+
+fn render_inline(element: &ElementRef) -> String {
+    match element.value().name() {
+        "br" => LINE_BREAK.to_string(),
+        "img" => match element.value().attr("alt").map(normalize_whitespace).filter(|alt| !alt.is_empty()) {
+            Some(alt) => format!("[{}]", alt),
+            None => String::new(),
+        },
+        "script" | "style" | "noscript" | "template" => String::new(),
+        _ => render_inline_children(element),
+    }
+}
+
+fn render_table(table: &ElementRef) -> String {
+    table.descendants()
+        .filter_map(ElementRef::wrap)
+        .filter(|row| row.value().name() == "tr")
+        .filter(|row| {
+            // Skip rows of nested tables
+            row.ancestors().filter_map(ElementRef::wrap)
+                .find(|ancestor| ancestor.value().name() == "table")
+                .map(|owner| owner.id()) == Some(table.id())
+        })
+        .map(|row| row.children()
+            .filter_map(ElementRef::wrap)
+            .filter(|cell| matches!(cell.value().name(), "td" | "th"))
+            .map(|cell| normalize_whitespace(&render_inline_children(&cell).replace(LINE_BREAK, " ")))
+            .collect::<Vec<_>>()
+            .join(" | "))
+        .filter(|row| !row.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Greedily wrap a line at word boundaries. Words longer than the width get
+/// a line of their own.
+fn wrap_line(line: &str, width: usize) -> String {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0;
+
+    for word in line.split_whitespace() {
+        let word_width = word.chars().count();
+        if current_width > 0 && current_width + 1 + word_width > width {
+            lines.push(std::mem::take(&mut current));
+            current_width = 0;
+        }
+        if current_width > 0 {
+            current.push(' ');
+            current_width += 1;
+        }
+        current.push_str(word);
+        current_width += word_width;
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+
+    lines.join("\n")
+}
+
+fn prefix_lines(text: &str, first: &str, rest: &str) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let prefix = if i == 0 { first } else { rest };
+            if line.is_empty() { prefix.trim_end().to_string() } else { format!("{}{}", prefix, line) }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn trim_empty_lines(lines: Vec<String>) -> Vec<String> {
+    let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !line.is_empty()).map(|end| end + 1).unwrap_or(start);
+    lines[start..end].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structure() {
+        let html = r#"<h1>Title</h1><p>First   paragraph
+            over two lines.</p><p>Second<br>with a break</p>
+            <ul><li>One<ol><li>Nested</li></ol></li><li>Two</li></ul>
+            <blockquote><p>Quoted</p></blockquote>
+            <pre>  fn main() {
+      indented();
+  }</pre><h2>Table</h2><table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table>"#;
+
+        assert_eq!(html_to_text(html, None), "Title\n=====\n\nFirst paragraph over two lines.\n\nSecond\nwith a break\n\n\
+            * One\n  1. Nested\n* Two\n\n> Quoted\n\n  fn main() {\n      indented();\n  }\n\nTable\n-----\n\nA | B\n1 | 2\n");
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let html = r#"<p>The quick brown fox jumps over the lazy dog.</p>
+            <ul><li>A list item that is long enough to wrap</li></ul>
+            <pre>A preformatted line that is never wrapped</pre><hr>"#;
+
+        assert_eq!(html_to_text(html, Some(16)), "The quick brown\nfox jumps over\nthe lazy dog.\n\n\
+            * A list item\n  that is long\n  enough to wrap\n\nA preformatted line that is never wrapped\n\n----------------\n");
+        assert_eq!(wrap_line("Supercalifragilistic word", 10), "Supercalifragilistic\nword");
+    }
+}
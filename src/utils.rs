@@ -1,8 +1,10 @@
 //! Utility functions for the Readability parser
 
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
 use scraper::{ElementRef, Element};
 use url::Url;
 use std::collections::HashSet;
+use crate::regexps::get_regexps;
 
 /// HTML elements that are considered phrasing content
 pub const PHRASING_ELEMS: &[&str] = &[
@@ -14,6 +16,7 @@ pub const PHRASING_ELEMS: &[&str] = &[
 ];
 
 /// Elements that can be converted from DIV to P
+#[allow(dead_code)] // Ported from Readability.js, not used by the parser yet
 pub const DIV_TO_P_ELEMS: &[&str] = &[
     "BLOCKQUOTE", "DL", "DIV", "IMG", "OL", "P", "PRE", "TABLE", "UL"
 ];
@@ -24,6 +27,13 @@ pub const UNLIKELY_ROLES: &[&str] = &[
     "alertdialog", "dialog"
 ];
 
+/// Elements that renderers lay out as blocks of their own rather than inline text
+pub const RENDER_BLOCK_TAGS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "details", "dd", "div", "dl", "dt", "fieldset",
+    "figcaption", "figure", "footer", "form", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr",
+    "li", "main", "nav", "ol", "p", "pre", "section", "summary", "table", "ul",
+];
+
 /// Presentational attributes that should be removed
 pub const PRESENTATIONAL_ATTRIBUTES: &[&str] = &[
     "align", "background", "bgcolor", "border", "cellpadding", "cellspacing",
@@ -253,12 +263,57 @@ pub fn word_count(text: &str) -> usize {
     text.split_whitespace().count()
 }
 
+/// Check if a character is a CJK ideograph or kana. These scripts do not
+/// separate words with spaces, so each character is read as a word.
+pub fn is_cjk_char(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF | 0x31F0..=0x31FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF
+        | 0x20000..=0x2FA1F)
+}
+
+/// Count the CJK characters and the space separated words of the rest of the text
+fn count_words_by_script(text: &str) -> (usize, usize) {
+    let mut cjk_chars = 0;
+    let rest: String = text.chars()
+        .map(|c| {
+            if is_cjk_char(c) {
+                cjk_chars += 1;
+                ' '
+            } else if matches!(c as u32, 0x3000..=0x303F | 0xFF00..=0xFFEF) && !c.is_alphanumeric() {
+                // CJK and fullwidth punctuation
+                ' '
+            } else {
+                c
+            }
+        })
+        .collect();
+    (cjk_chars, word_count(&rest))
+}
+
+/// Script-aware word count: CJK characters count individually, other text
+/// is split on whitespace
+pub fn count_words(text: &str) -> usize {
+    let (cjk_chars, words) = count_words_by_script(text);
+    cjk_chars + words
+}
+
+/// Estimated reading time in whole minutes, at least one for any text
+pub fn reading_time_minutes(text: &str, words_per_minute: usize, cjk_chars_per_minute: usize) -> usize {
+    let (cjk_chars, words) = count_words_by_script(text);
+    if cjk_chars + words == 0 {
+        return 0;
+    }
+    let minutes = words as f64 / words_per_minute.max(1) as f64
+        + cjk_chars as f64 / cjk_chars_per_minute.max(1) as f64;
+    (minutes.ceil() as usize).max(1)
+}
+
 /// Check if text looks like a title
 pub fn is_title_candidate(text: &str, current_title: Option<&str>) -> bool {
     let word_count = word_count(text);
     
     // Should be reasonable length - more restrictive for titles
-    if word_count < 2 || word_count > 10 || text.len() > 80 {
+    if !(2..=10).contains(&word_count) || text.len() > 80 {
         return false;
     }
     
@@ -291,17 +346,32 @@ pub fn text_similarity(text_a: &str, text_b: &str) -> f64 {
 }
 
 /// Unescape HTML entities
+///
+/// Handles the named references XML knows about plus decimal and hexadecimal
+/// character references, in a single pass so `&amp;lt;` becomes `&lt;`.
 pub fn unescape_html_entities(text: &str) -> String {
-    // First handle &amp; (must be done before other & entities)
-    let text = text.replace("&amp;", "&");
-    
-    // Then handle other entities
-    text.replace("&lt;", "<")
-        .replace("&gt;", ">")
-        .replace("&quot;", "\"")
-        .replace("&apos;", "'")
-        .replace("&#39;", "'")
-        // Note: We don't unescape &nbsp; to maintain the test expectation
+    get_regexps().html_entity.replace_all(text, |caps: &regex::Captures| {
+        if let Some(name) = caps.get(1) {
+            return match name.as_str() {
+                "quot" => "\"",
+                "amp" => "&",
+                "apos" => "'",
+                "lt" => "<",
+                _ => ">",
+            }.to_string();
+        }
+
+        let code = match (caps.get(2), caps.get(3)) {
+            (Some(hex), _) => u32::from_str_radix(hex.as_str(), 16).ok(),
+            (_, Some(dec)) => dec.as_str().parse::<u32>().ok(),
+            _ => None,
+        };
+        // Invalid code points are replaced the way a conforming HTML parser would
+        code.filter(|&c| c != 0)
+            .and_then(char::from_u32)
+            .unwrap_or('\u{FFFD}')
+            .to_string()
+    }).to_string()
 }
 
 /// Remove extra whitespace and normalize text
@@ -310,6 +380,129 @@ pub fn clean_text(text: &str) -> String {
     normalize_whitespace(&unescaped)
 }
 
+/// Parse a date as found in metadata into a timestamp
+///
+/// Accepts RFC 3339 / ISO 8601 (with or without offset or time), RFC 2822 and
+/// plain `YYYY-MM-DD HH:MM:SS`. Values without an offset are taken as UTC.
+pub fn parse_date(text: &str) -> Option<DateTime<FixedOffset>> {
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    if let Ok(date) = DateTime::parse_from_rfc3339(text) {
+        return Some(date);
+    }
+    if let Ok(date) = DateTime::parse_from_rfc2822(text) {
+        return Some(date);
+    }
+    for format in ["%Y-%m-%dT%H:%M:%S%.f%z", "%Y-%m-%dT%H:%M%z", "%Y-%m-%d %H:%M:%S%.f%z"] {
+        if let Ok(date) = DateTime::parse_from_str(text, format) {
+            return Some(date);
+        }
+    }
+
+    let utc = FixedOffset::east_opt(0)?;
+    for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%d %H:%M"] {
+        if let Ok(date) = NaiveDateTime::parse_from_str(text, format) {
+            return Some(date.and_utc().with_timezone(&utc));
+        }
+    }
+    for format in ["%Y-%m-%d", "%Y/%m/%d"] {
+        if let Ok(date) = NaiveDate::parse_from_str(text, format) {
+            return Some(date.and_hms_opt(0, 0, 0)?.and_utc().with_timezone(&utc));
+        }
+    }
+
+    None
+}
+
+/// Truncate text to at most `max_chars` characters, cutting at a word
+/// boundary and marking the cut with an ellipsis
+pub fn truncate_at_word_boundary(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    // Leave room for the ellipsis
+    let limit = max_chars.saturating_sub(1);
+    let cut = text.char_indices().nth(limit).map(|(i, _)| i).unwrap_or(text.len());
+    let head = &text[..cut];
+
+    // Back up to the last whitespace unless the cut already falls on one
+    let next_is_space = text[cut..].starts_with(char::is_whitespace);
+    let word_head = match head.rfind(char::is_whitespace) {
+        Some(space) if !next_is_space => &head[..space],
+        _ => head,
+    };
+
+    let trim = |head: &'_ str| -> String {
+        head.trim().trim_end_matches(|c: char| c.is_ascii_punctuation() && c != ')' && c != '"').to_string()
+    };
+    // Cut mid-word when no whole word fits
+    let head = Some(trim(word_head))
+        .filter(|head| !head.is_empty())
+        .unwrap_or_else(|| trim(head));
+    if head.is_empty() {
+        // Too short for the ellipsis
+        return text.trim().chars().take(max_chars).collect();
+    }
+    format!("{}…", head)
+}
+
+/// Check if a character belongs to a right-to-left script (Hebrew, Arabic,
+/// Syriac, Thaana, N'Ko and their presentation forms)
+pub fn is_rtl_char(c: char) -> bool {
+    matches!(c as u32,
+        0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF)
+}
+
+/// Check if most letters of the text are written in a right-to-left script
+pub fn is_mostly_rtl(text: &str) -> bool {
+    let (rtl, ltr) = text.chars()
+        .filter(|c| c.is_alphabetic())
+        .fold((0usize, 0usize), |(rtl, ltr), c| {
+            if is_rtl_char(c) { (rtl + 1, ltr) } else { (rtl, ltr + 1) }
+        });
+    rtl > ltr
+}
+
+/// A candidate from an image `srcset` attribute
+#[derive(Debug, Clone, PartialEq)]
+pub struct SrcsetCandidate {
+    pub url: String,
+    /// Width descriptor (`800w`)
+    pub width: Option<u32>,
+    /// Pixel density descriptor (`2x`)
+    pub density: Option<f64>,
+}
+
+/// Parse a `srcset` attribute into its candidates
+pub fn parse_srcset(srcset: &str) -> Vec<SrcsetCandidate> {
+    get_regexps().srcset_url.captures_iter(srcset)
+        .map(|caps| {
+            let url = caps[1].trim_end_matches(',').to_string();
+            let descriptor = caps.get(2).map(|d| d.as_str().trim()).unwrap_or("");
+            let (width, density) = if let Some(width) = descriptor.strip_suffix('w') {
+                (width.parse::<f64>().ok().map(|w| w as u32), None)
+            } else if let Some(density) = descriptor.strip_suffix('x') {
+                (None, density.parse().ok())
+            } else {
+                (None, None)
+            };
+            SrcsetCandidate { url, width, density }
+        })
+        .filter(|candidate| !candidate.url.is_empty())
+        .collect()
+}
+
+/// Parse a dimension attribute such as `640` or `640px`; percentages are unknown sizes
+pub fn parse_dimension(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let value = value.strip_suffix("px").unwrap_or(value).trim();
+    value.parse::<f64>().ok().filter(|v| *v > 0.0).map(|v| v as u32)
+}
+
 /// Get link density for an element
 pub fn get_link_density(element: &ElementRef) -> f64 {
     let total_text_length = get_inner_text(element, false).len();
@@ -387,6 +580,8 @@ mod tests {
         assert_eq!(unescape_html_entities("&lt;div&gt;"), "<div>");
         assert_eq!(unescape_html_entities("&quot;hello&quot;"), "\"hello\"");
         assert_eq!(unescape_html_entities("&amp;nbsp;"), "&nbsp;");
+        assert_eq!(unescape_html_entities("Caf&#233; &#x2014; Bar"), "Café — Bar");
+        assert_eq!(unescape_html_entities("&#0;"), "\u{FFFD}");
     }
 
     #[test]
@@ -396,6 +591,81 @@ mod tests {
         assert!(!is_title_candidate("This is way too long to be a reasonable title for an article", None)); // Too long
     }
 
+    #[test]
+    fn test_parse_date() {
+        let date = parse_date("2023-04-05T10:20:30+02:00").unwrap();
+        assert_eq!(date.to_rfc3339(), "2023-04-05T10:20:30+02:00");
+        let date = parse_date("2023-04-05T10:20:30.123Z").unwrap();
+        assert_eq!(date.to_rfc3339(), "2023-04-05T10:20:30.123+00:00");
+        let date = parse_date("Wed, 05 Apr 2023 10:20:30 GMT").unwrap();
+        assert_eq!(date.to_rfc3339(), "2023-04-05T10:20:30+00:00");
+        let date = parse_date("2023-04-05").unwrap();
+        assert_eq!(date.to_rfc3339(), "2023-04-05T00:00:00+00:00");
+        let date = parse_date("2023-04-05T10:20:30+0200").unwrap();
+        assert_eq!(date.to_rfc3339(), "2023-04-05T10:20:30+02:00");
+        assert!(parse_date("last Tuesday").is_none());
+        assert!(parse_date("").is_none());
+    }
+
+    #[test]
+    fn test_truncate_at_word_boundary() {
+        assert_eq!(truncate_at_word_boundary("short text", 20), "short text");
+        assert_eq!(truncate_at_word_boundary("The quick brown fox jumps", 12), "The quick…");
+        assert_eq!(truncate_at_word_boundary("The quick, brown fox", 11), "The quick…");
+        assert_eq!(truncate_at_word_boundary("Ünïcödé wörds everywhere", 14), "Ünïcödé wörds…");
+        assert_eq!(truncate_at_word_boundary("Supercalifragilistic", 5), "Supe…");
+        assert_eq!(truncate_at_word_boundary(" Supercalifragilistic word", 5), "Sup…");
+        assert_eq!(truncate_at_word_boundary("... Supercalifragilistic", 8), "... Sup…");
+        assert_eq!(truncate_at_word_boundary("Supercalifragilistic", 1), "S");
+    }
+
+    #[test]
+    fn test_count_words() {
+        assert_eq!(count_words("The quick brown fox"), 4);
+        assert_eq!(count_words("这是一篇文章。"), 6);
+        assert_eq!(count_words("日本語のテキスト、です"), 10);
+        assert_eq!(count_words("Rust 是一种语言"), 6);
+        assert_eq!(count_words("한국어 문장입니다"), 2);
+        assert_eq!(count_words("   "), 0);
+    }
+
+    #[test]
+    fn test_reading_time_minutes() {
+        assert_eq!(reading_time_minutes("", 200, 500), 0);
+        assert_eq!(reading_time_minutes("a few words", 200, 500), 1);
+        assert_eq!(reading_time_minutes(&"word ".repeat(401), 200, 500), 3);
+        assert_eq!(reading_time_minutes(&"字".repeat(1000), 200, 500), 2);
+        assert_eq!(reading_time_minutes(&"word ".repeat(400), 0, 0), 400);
+    }
+
+    #[test]
+    fn test_is_mostly_rtl() {
+        assert!(is_mostly_rtl("هذا محتوى المقالة with English"));
+        assert!(is_mostly_rtl("זהו מאמר בעברית"));
+        assert!(!is_mostly_rtl("An English article quoting مقالة once"));
+        assert!(!is_mostly_rtl("12345 !!!"));
+    }
+
+    #[test]
+    fn test_parse_srcset() {
+        let candidates = parse_srcset("small.jpg 320w, medium.jpg 800w,large.jpg 1600w");
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(candidates[1], SrcsetCandidate { url: "medium.jpg".to_string(), width: Some(800), density: None });
+        assert_eq!(candidates[2].url, "large.jpg");
+
+        let candidates = parse_srcset("a.jpg, b.jpg 2x");
+        assert_eq!(candidates[0], SrcsetCandidate { url: "a.jpg".to_string(), width: None, density: None });
+        assert_eq!(candidates[1].density, Some(2.0));
+    }
+
+    #[test]
+    fn test_parse_dimension() {
+        assert_eq!(parse_dimension("640"), Some(640));
+        assert_eq!(parse_dimension(" 640px "), Some(640));
+        assert_eq!(parse_dimension("100%"), None);
+        assert_eq!(parse_dimension("0"), None);
+    }
+
     #[test]
     fn test_get_char_count() {
         assert_eq!(get_char_count("hello,world,test", Some(',')), 2);